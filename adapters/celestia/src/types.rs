@@ -91,6 +91,12 @@ impl SlotData for FilteredCelestiaBlock {
     fn header(&self) -> &Self::BlockHeader {
         &self.header
     }
+
+    fn extra_data_for_storage(&self) -> Vec<u8> {
+        self.header
+            .try_to_vec()
+            .expect("serialization to vec is infallible")
+    }
 }
 
 impl FilteredCelestiaBlock {
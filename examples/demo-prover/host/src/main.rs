@@ -55,7 +55,8 @@ async fn main() -> Result<(), anyhow::Error> {
     let sequencer_private_key = DefaultPrivateKey::generate();
 
     let mut demo_runner = NativeAppRunner::<Risc0Host>::new(rollup_config.runner.clone());
-    let is_storage_empty = demo_runner.get_storage().is_empty();
+    let storage = demo_runner.get_storage();
+    let is_storage_empty = storage.is_empty();
     let demo = demo_runner.inner_mut();
 
     if is_storage_empty {
@@ -70,9 +71,7 @@ async fn main() -> Result<(), anyhow::Error> {
         demo.init_chain(genesis_config);
     }
 
-    demo.begin_slot(Default::default());
-    let (prev_state_root, _) = demo.end_slot();
-    let mut prev_state_root = prev_state_root.0;
+    let mut prev_state_root = storage.get_root_hash(storage.latest_version())?;
 
     for height in rollup_config.start_height..=rollup_config.start_height + 30 {
         let mut host = Risc0Host::new(ROLLUP_ELF);
@@ -1,6 +1,9 @@
 use demo_stf::runner_config::Config as RunnerConfig;
 use jupiter::da_service::DaServiceConfig;
 use serde::Deserialize;
+use sov_db::ledger_db::NodeMode;
+use sov_db::rocks_db_config::RocksdbConfig;
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct RpcConfig {
@@ -8,12 +11,44 @@ pub struct RpcConfig {
     pub bind_port: u16,
 }
 
+/// The address of the HTTP server exposing the prometheus metrics of the node at `/metrics`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MetricsConfig {
+    pub bind_host: String,
+    pub bind_port: u16,
+}
+
+/// Runs the node as a replica which only serves RPC queries, from the databases of a node executing
+/// the rollup in another process.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReplicaConfig {
+    /// The data directory of the node executing the rollup. The replica keeps its own files in `runner.storage.path`.
+    pub primary_path: PathBuf,
+    /// How often the replica catches up with the node, in milliseconds.
+    pub catch_up_interval_ms: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct RollupConfig {
     pub start_height: u64,
+    /// Execute blocks as soon as they are produced by the da layer, instead of waiting for them to be finalized.
+    /// Blocks executed this way are soft confirmations: if the da layer reorgs, the node rewinds to the fork point.
+    #[serde(default)]
+    pub soft_confirmations: bool,
+    /// How much of the ledger history the node keeps. Defaults to the whole history.
+    #[serde(default)]
+    pub node_mode: NodeMode,
+    /// The tuning of the rocksdb instance of the ledger. The storage is tuned in `runner.storage.rocksdb`.
+    #[serde(default)]
+    pub ledger_db: RocksdbConfig,
     pub da: DaServiceConfig,
     pub runner: RunnerConfig,
     pub rpc_config: RpcConfig,
+    /// The metrics are only served if this section is present.
+    #[serde(default)]
+    pub metrics: Option<MetricsConfig>,
+    #[serde(default)]
+    pub replica: Option<ReplicaConfig>,
 }
 
 #[cfg(test)]
@@ -22,7 +57,6 @@ mod tests {
 
     use demo_stf::runner_config::{from_toml_path, StorageConfig};
     use std::io::Write;
-    use std::path::PathBuf;
     use tempfile::NamedTempFile;
 
     fn create_config_from(content: &str) -> NamedTempFile {
@@ -41,9 +75,19 @@ mod tests {
             max_celestia_response_body_size = 980
             [runner.storage]
             path = "/tmp"
+            [runner.storage.rocksdb]
+            bloom_filter_bits_per_key = 0
+            [ledger_db]
+            max_open_files = 100
             [rpc_config]
             bind_host = "127.0.0.1"
             bind_port = 12345
+            [metrics]
+            bind_host = "127.0.0.1"
+            bind_port = 9845
+            [node_mode]
+            type = "pruned"
+            keep_slots = 1000
         "#;
 
         let config_file = create_config_from(config);
@@ -51,6 +95,12 @@ mod tests {
         let config: RollupConfig = from_toml_path(config_file.path()).unwrap();
         let expected = RollupConfig {
             start_height: 31337,
+            soft_confirmations: false,
+            node_mode: NodeMode::Pruned { keep_slots: 1000 },
+            ledger_db: RocksdbConfig {
+                max_open_files: 100,
+                ..Default::default()
+            },
             da: DaServiceConfig {
                 celestia_rpc_auth_token: "SECRET_RPC_TOKEN".to_string(),
                 celestia_rpc_address: "http://localhost:11111/".into(),
@@ -59,12 +109,23 @@ mod tests {
             runner: RunnerConfig {
                 storage: StorageConfig {
                     path: PathBuf::from("/tmp"),
+                    rocksdb: RocksdbConfig {
+                        bloom_filter_bits_per_key: 0,
+                        ..Default::default()
+                    },
                 },
+                tx_body_policy: Default::default(),
+                parallel_execution: false,
             },
             rpc_config: RpcConfig {
                 bind_host: "127.0.0.1".to_string(),
                 bind_port: 12345,
             },
+            metrics: Some(MetricsConfig {
+                bind_host: "127.0.0.1".to_string(),
+                bind_port: 9845,
+            }),
+            replica: None,
         };
         assert_eq!(config, expected);
     }
@@ -1,11 +1,18 @@
+use std::sync::Arc;
+
+use jsonrpsee::core::server::rpc_module::SubscriptionSink;
+use jsonrpsee::types::SubscriptionResult;
 use jsonrpsee::RpcModule;
 use serde::{de::DeserializeOwned, Serialize};
-use sov_db::ledger_db::LedgerDB;
+use sov_db::ledger_db::{LedgerDB, SlotNotification};
 use sov_rollup_interface::rpc::{
-    BatchIdentifier, EventIdentifier, LedgerRpcProvider, SlotIdentifier, TxIdentifier,
+    BatchIdentifier, EventIdentifier, EventsByKeyQuery, LedgerRpcProvider, QueryMode,
+    SlotIdentifier, TxResponse,
 };
+use sov_rollup_interface::stf::EventKey;
+use tokio::sync::broadcast::error::RecvError;
 
-use self::query_args::{extract_query_args, QueryArgs};
+use self::query_args::{extract_query_args, extract_tx_query_args, QueryArgs, TxQueryArgs};
 
 /// Registers the following RPC methods
 /// - `ledger_head`
@@ -15,11 +22,20 @@ use self::query_args::{extract_query_args, QueryArgs};
 /// - ledger_getBatches
 ///    Example Query: `curl -X POST -H "Content-Type: application/json" -d '{"jsonrpc":"2.0","method":"ledger_getBatches","params":[[1, 2], "Standard"],"id":1}' http://127.0.0.1:12345`
 /// - ledger_getTransactions
+///    Takes an optional third argument which, if `true`, adds the call message decoded with `decode_tx_body` to the transactions whose body was saved.
 ///    Example Query: `curl -X POST -H "Content-Type: application/json" -d '{"jsonrpc":"2.0","method":"ledger_getBatches","params":[[1, 2], "Full"],"id":1}' http://127.0.0.1:12345`
 /// - ledger_getEvents
 ///    Example Query: `curl -X POST -H "Content-Type: application/json" -d '{"jsonrpc":"2.0","method":"ledger_getBatches","params":[1, 2],"id":1}' http://127.0.0.1:12345`
+/// - ledger_getEventsByKey
+///    Returns a page of the events with the given key, optionally restricted to a range of transaction numbers.
+///    Pass the `next_cursor` of the response as `cursor` to fetch the next page.
+///    Example Query: `curl -X POST -H "Content-Type: application/json" -d '{"jsonrpc":"2.0","method":"ledger_getEventsByKey","params":[{"key": [107, 101, 121], "tx_range": {"start": 10, "end": 20}, "limit": 100}],"id":1}' http://127.0.0.1:12345`
+/// - ledger_getStateRoot
+///    Returns the state root after the given slot was executed, and the hash of the witness produced while executing it.
+///    Example Query: `curl -X POST -H "Content-Type: application/json" -d '{"jsonrpc":"2.0","method":"ledger_getStateRoot","params":[1],"id":1}' http://127.0.0.1:12345`
 fn register_ledger_rpc_methods<B: Serialize + DeserializeOwned, T: Serialize + DeserializeOwned>(
     rpc: &mut RpcModule<LedgerDB>,
+    decode_tx_body: TxBodyDecoder,
 ) -> Result<(), jsonrpsee::core::Error> {
     rpc.register_method("ledger_getHead", move |_, db| {
         db.get_head::<B, T>().map_err(|e| e.into())
@@ -37,8 +53,20 @@ fn register_ledger_rpc_methods<B: Serialize + DeserializeOwned, T: Serialize + D
     })?;
 
     rpc.register_method("ledger_getTransactions", move |params, db| {
-        let args: QueryArgs<TxIdentifier> = extract_query_args(params)?;
-        db.get_transactions::<T>(&args.0, args.1)
+        let args: TxQueryArgs = extract_tx_query_args(params)?;
+        let txs = db.get_transactions::<T>(&args.0, args.1)?;
+        txs.into_iter()
+            .map(|tx| {
+                tx.map(|tx| {
+                    let call = match &tx.body {
+                        Some(body) if args.2 => Some(decode_tx_body(body)?),
+                        _ => None,
+                    };
+                    Ok::<_, anyhow::Error>(DecodedTxResponse { tx, call })
+                })
+                .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()
             .map_err(|e| e.into())
     })?;
 
@@ -47,24 +75,202 @@ fn register_ledger_rpc_methods<B: Serialize + DeserializeOwned, T: Serialize + D
         db.get_events(&ids).map_err(|e| e.into())
     })?;
 
+    rpc.register_method("ledger_getEventsByKey", move |params, db| {
+        let query: EventsByKeyQuery = params.one()?;
+        db.get_events_by_key(&query).map_err(|e| e.into())
+    })?;
+
+    rpc.register_method("ledger_getStateRoot", move |params, db| {
+        let slot_number: u64 = params.one()?;
+        db.get_state_root(slot_number).map_err(|e| e.into())
+    })?;
+
+    Ok(())
+}
+
+/// Registers the following subscriptions, which are served over websockets on the same address as the RPC methods.
+/// Each subscription sends one notification per item, in the order the items were committed to the ledger.
+/// - ledger_subscribeSlots
+///    Notifies the subscriber of every new slot. Takes an optional `QueryMode`, with the same semantics as `ledger_getSlots`.
+///    Example Subscription: `{"jsonrpc":"2.0","method":"ledger_subscribeSlots","params":["Compact"],"id":1}`
+/// - ledger_subscribeTransactions
+///    Notifies the subscriber of every new transaction. Takes an optional `QueryMode`, with the same semantics as `ledger_getTransactions`.
+///    Example Subscription: `{"jsonrpc":"2.0","method":"ledger_subscribeTransactions","params":["Full"],"id":1}`
+/// - ledger_subscribeEvents
+///    Notifies the subscriber of every new event with the given key.
+///    Example Subscription: `{"jsonrpc":"2.0","method":"ledger_subscribeEvents","params":[[107, 101, 121]],"id":1}`
+fn register_ledger_subscriptions<B, T>(
+    rpc: &mut RpcModule<LedgerDB>,
+) -> Result<(), jsonrpsee::core::Error>
+where
+    B: Serialize + DeserializeOwned + Send + 'static,
+    T: Serialize + DeserializeOwned + Send + 'static,
+{
+    rpc.register_subscription(
+        "ledger_subscribeSlots",
+        "ledger_slot",
+        "ledger_unsubscribeSlots",
+        |params, mut sink, db| {
+            let query_mode = match params.sequence().optional_next::<QueryMode>() {
+                Ok(query_mode) => query_mode.unwrap_or_default(),
+                Err(e) => {
+                    let _ = sink.reject(e);
+                    return Ok(());
+                }
+            };
+            forward_slot_notifications(sink, db, move |db, slot| {
+                Ok(db
+                    .get_slot_by_number::<B, T>(slot.slot_number, query_mode)?
+                    .into_iter()
+                    .collect())
+            })
+        },
+    )?;
+
+    rpc.register_subscription(
+        "ledger_subscribeTransactions",
+        "ledger_transaction",
+        "ledger_unsubscribeTransactions",
+        |params, mut sink, db| {
+            let query_mode = match params.sequence().optional_next::<QueryMode>() {
+                Ok(query_mode) => query_mode.unwrap_or_default(),
+                Err(e) => {
+                    let _ = sink.reject(e);
+                    return Ok(());
+                }
+            };
+            forward_slot_notifications(sink, db, move |db, slot| {
+                let mut txs = Vec::new();
+                for number in slot.tx_range {
+                    txs.extend(db.get_tx_by_number::<T>(number, query_mode)?);
+                }
+                Ok(txs)
+            })
+        },
+    )?;
+
+    rpc.register_subscription(
+        "ledger_subscribeEvents",
+        "ledger_event",
+        "ledger_unsubscribeEvents",
+        |params, mut sink, db| {
+            let key: EventKey = match params.one() {
+                Ok(key) => key,
+                Err(e) => {
+                    let _ = sink.reject(e);
+                    return Ok(());
+                }
+            };
+            forward_slot_notifications(sink, db, move |db, slot| {
+                let mut query = EventsByKeyQuery {
+                    key: key.clone(),
+                    tx_range: Some(slot.tx_range),
+                    cursor: None,
+                    limit: None,
+                };
+                let mut events = Vec::new();
+                loop {
+                    let page = db.get_events_by_key(&query)?;
+                    events.extend(page.events);
+                    match page.next_cursor {
+                        Some(cursor) => query.cursor = Some(cursor),
+                        None => return Ok(events),
+                    }
+                }
+            })
+        },
+    )?;
+
     Ok(())
 }
 
-pub fn get_ledger_rpc<B: Serialize + DeserializeOwned, T: Serialize + DeserializeOwned>(
+/// Accepts the subscription, and sends the items returned by `get_items` for every slot committed to the ledger,
+/// until the subscriber goes away. Subscribers which fall too far behind skip the slots they missed.
+fn forward_slot_notifications<I, F>(
+    mut sink: SubscriptionSink,
+    db: Arc<LedgerDB>,
+    get_items: F,
+) -> SubscriptionResult
+where
+    I: Serialize + Send + 'static,
+    F: Fn(&LedgerDB, SlotNotification) -> Result<Vec<I>, anyhow::Error> + Send + Sync + 'static,
+{
+    sink.accept()?;
+    let mut slots = db.subscribe_slots();
+    let get_items = Arc::new(get_items);
+    tokio::spawn(async move {
+        loop {
+            let slot = match slots.recv().await {
+                Ok(slot) => slot,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Subscriber lagged behind, skipping {} slots", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => return,
+            };
+            // Reading the ledger blocks, so it runs on the blocking thread pool.
+            let (db, get_items) = (db.clone(), get_items.clone());
+            let read = tokio::task::spawn_blocking(move || get_items(&db, slot)).await;
+            let items = match read.map_err(anyhow::Error::from).and_then(|items| items) {
+                Ok(items) => items,
+                Err(e) => {
+                    tracing::error!("Failed to read the ledger for a subscription: {}", e);
+                    return;
+                }
+            };
+            for item in items {
+                if !matches!(sink.send(&item), Ok(true)) {
+                    return;
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Decodes a transaction body saved in the ledger into the JSON representation of its call message.
+pub type TxBodyDecoder = fn(&[u8]) -> anyhow::Result<serde_json::Value>;
+
+/// A transaction, together with its decoded call message if it was requested.
+#[derive(Serialize)]
+struct DecodedTxResponse<T> {
+    #[serde(flatten)]
+    tx: TxResponse<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    call: Option<serde_json::Value>,
+}
+
+pub fn get_ledger_rpc<B, T>(
     ledger_db: LedgerDB,
-) -> RpcModule<LedgerDB> {
+    decode_tx_body: TxBodyDecoder,
+) -> RpcModule<LedgerDB>
+where
+    B: Serialize + DeserializeOwned + Send + 'static,
+    T: Serialize + DeserializeOwned + Send + 'static,
+{
     let mut rpc = RpcModule::new(ledger_db);
-    register_ledger_rpc_methods::<B, T>(&mut rpc).expect("Failed to register ledger RPC methods");
+    register_ledger_rpc_methods::<B, T>(&mut rpc, decode_tx_body)
+        .expect("Failed to register ledger RPC methods");
+    register_ledger_subscriptions::<B, T>(&mut rpc)
+        .expect("Failed to register ledger RPC subscriptions");
     rpc
 }
 
 mod query_args {
     use serde::de::DeserializeOwned;
-    use sov_rollup_interface::rpc::QueryMode;
+    use sov_rollup_interface::rpc::{QueryMode, TxIdentifier};
 
     #[derive(serde::Deserialize)]
     pub struct QueryArgs<I>(pub Vec<I>, #[serde(default)] pub QueryMode);
 
+    /// The args of `ledger_getTransactions`: the ids, the query mode, and whether to decode the transaction bodies.
+    #[derive(serde::Deserialize)]
+    pub struct TxQueryArgs(
+        pub Vec<TxIdentifier>,
+        #[serde(default)] pub QueryMode,
+        #[serde(default)] pub bool,
+    );
+
     /// Extract the args from an RPC query, being liberal in what is accepted.
     /// To query for a list of items, users can either pass a list of ids, or tuple containing a list of ids and a query mode
     pub fn extract_query_args<I: DeserializeOwned>(
@@ -76,4 +282,15 @@ mod query_args {
         let ids: Vec<I> = params.parse()?;
         Ok(QueryArgs(ids, Default::default()))
     }
+
+    /// Extract the args of a transaction query, accepting the same forms as [`extract_query_args`].
+    pub fn extract_tx_query_args(
+        params: jsonrpsee::types::Params,
+    ) -> Result<TxQueryArgs, jsonrpsee::core::Error> {
+        if let Ok(args) = params.parse() {
+            return Ok(args);
+        }
+        let QueryArgs(ids, query_mode) = extract_query_args(params)?;
+        Ok(TxQueryArgs(ids, query_mode, false))
+    }
 }
@@ -1,40 +1,54 @@
 mod config;
 mod ledger_rpc;
+mod metrics;
 
-use crate::config::RollupConfig;
+use crate::config::{ReplicaConfig, RollupConfig};
 use anyhow::Context;
 use const_rollup_config::{ROLLUP_NAMESPACE_RAW, SEQUENCER_DA_ADDRESS};
-use demo_stf::app::{DefaultContext, DemoBatchReceipt, DemoTxReceipt};
+use demo_stf::app::{
+    decode_tx_body, get_rpc_methods, DefaultContext, DemoBatchReceipt, DemoTxReceipt,
+};
 use demo_stf::app::{DefaultPrivateKey, NativeAppRunner};
 use demo_stf::genesis_config::create_demo_genesis_config;
 use demo_stf::runner_config::from_toml_path;
 use demo_stf::runtime::GenesisConfig;
 use jsonrpsee::core::server::rpc_module::Methods;
+use jsonrpsee::RpcModule;
 use jupiter::da_service::CelestiaService;
 use jupiter::types::NamespaceId;
 use jupiter::verifier::CelestiaVerifier;
 use jupiter::verifier::RollupParams;
 use risc0_adapter::host::Risc0Host;
-use sov_db::ledger_db::{LedgerDB, SlotCommit};
+use sha2::{Digest, Sha256};
+use sov_db::ledger_db::{LedgerDB, NodeMode, SlotCommit};
+use sov_db::rocks_db_config::RocksdbConfig;
+use sov_modules_api::Spec;
 use sov_rollup_interface::da::DaVerifier;
+use sov_rollup_interface::rpc::LedgerRpcProvider;
 use sov_rollup_interface::services::da::{DaService, SlotData};
 use sov_rollup_interface::stf::{StateTransitionFunction, StateTransitionRunner};
+use sov_rollup_interface::traits::BlockHeaderTrait;
 use sov_state::Storage;
 use std::env;
 use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
 use tracing::Level;
 use tracing::{debug, info};
 
 // RPC related imports
-use demo_stf::app::get_rpc_methods;
 use sov_modules_api::RpcRunner;
 
 // The rollup stores its data in the namespace b"sov-test" on Celestia
 // You can change this constant to point your rollup at a different namespace
 const ROLLUP_NAMESPACE: NamespaceId = NamespaceId(ROLLUP_NAMESPACE_RAW);
 
-pub fn initialize_ledger(path: impl AsRef<std::path::Path>) -> LedgerDB {
-    LedgerDB::with_path(path).expect("Ledger DB failed to open")
+pub fn initialize_ledger(
+    path: impl AsRef<Path>,
+    mode: NodeMode,
+    rocksdb_config: &RocksdbConfig,
+) -> LedgerDB {
+    LedgerDB::with_config(path, mode, rocksdb_config).expect("Ledger DB failed to open")
 }
 
 async fn start_rpc_server(methods: impl Into<Methods>, address: SocketAddr) {
@@ -46,6 +60,66 @@ async fn start_rpc_server(methods: impl Into<Methods>, address: SocketAddr) {
     futures::future::pending::<()>().await;
 }
 
+/// Returns the RPC methods of the modules, together with the ledger RPC methods.
+fn get_all_rpc_methods(
+    ledger_db: &LedgerDB,
+    storage: <DefaultContext as Spec>::Storage,
+) -> RpcModule<()> {
+    let mut methods = get_rpc_methods(storage);
+    let ledger_rpc_module = ledger_rpc::get_ledger_rpc::<DemoBatchReceipt, DemoTxReceipt>(
+        ledger_db.clone(),
+        decode_tx_body,
+    );
+    methods
+        .merge(ledger_rpc_module)
+        .expect("Failed to merge rpc modules");
+    methods
+}
+
+/// Serves RPC queries from read-only secondary instances of the databases of a node running in another process,
+/// without executing anything. The secondaries keep their own files in `runner.storage.path`.
+async fn run_replica(
+    replica: &ReplicaConfig,
+    rollup_config: &RollupConfig,
+    address: SocketAddr,
+) -> Result<(), anyhow::Error> {
+    let path = &rollup_config.runner.storage.path;
+    let ledger_db = LedgerDB::secondary_with_path(
+        &replica.primary_path,
+        path,
+        rollup_config.node_mode,
+        &rollup_config.ledger_db,
+    )?;
+    let storage = <DefaultContext as Spec>::Storage::secondary_with_path(
+        &replica.primary_path,
+        path,
+        &rollup_config.runner.storage.rocksdb,
+    )?;
+
+    let methods = get_all_rpc_methods(&ledger_db, storage.clone());
+    let _handle = tokio::spawn(async move {
+        start_rpc_server(methods, address).await;
+    });
+
+    info!(
+        "Serving queries for the node at {}",
+        replica.primary_path.display()
+    );
+    let mut interval = tokio::time::interval(Duration::from_millis(replica.catch_up_interval_ms));
+    loop {
+        interval.tick().await;
+        // The node commits the state before the ledger, so catching up in the same order never
+        // serves a slot whose state is missing. Catching up reads the new files of the primary,
+        // so it runs on the blocking thread pool.
+        let (ledger_db, storage) = (ledger_db.clone(), storage.clone());
+        tokio::task::spawn_blocking(move || {
+            storage.catch_up_with_primary()?;
+            ledger_db.catch_up_with_primary()
+        })
+        .await??;
+    }
+}
+
 /// Configure our rollup with a centralized sequencer using the SEQUENCER_DA_ADDRESS
 /// address constant. Since the centralize sequencer's address is consensus critical,
 /// it has to be hardcoded as a constant, rather than read from the config at runtime.
@@ -67,6 +141,40 @@ pub fn get_genesis_config() -> GenesisConfig<DefaultContext> {
     )
 }
 
+/// Returns the state root after the slot `slot_number` was executed, as recorded in the ledger.
+/// Before the first slot, and for slots committed without a state root, the storage must have been
+/// rolled back to the slot: its latest root is returned instead.
+fn get_state_root(
+    ledger_db: &LedgerDB,
+    storage: &<DefaultContext as Spec>::Storage,
+    slot_number: u64,
+) -> Result<Vec<u8>, anyhow::Error> {
+    match ledger_db.get_state_root(slot_number)? {
+        Some(response) => Ok(response.state_root),
+        None => Ok(storage.get_root_hash(storage.latest_version())?.to_vec()),
+    }
+}
+
+/// Returns the number of the last slot, at most `slot_number`, whose block is still on the chain
+/// of the DA layer, or `0` if none of the slots are.
+async fn find_fork_point(
+    da_service: &CelestiaService,
+    ledger_db: &LedgerDB,
+    rollup_start_height: u64,
+    mut slot_number: u64,
+) -> Result<u64, anyhow::Error> {
+    while slot_number > 0 {
+        let block = da_service
+            .get_block_at(rollup_start_height + slot_number - 1)
+            .await?;
+        if ledger_db.get_slot_hash(slot_number)? == Some(block.hash()) {
+            break;
+        }
+        slot_number -= 1;
+    }
+    Ok(slot_number)
+}
+
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     let rollup_config_path = env::args()
@@ -75,7 +183,7 @@ async fn main() -> Result<(), anyhow::Error> {
     debug!("Starting demo rollup with config {}", rollup_config_path);
     let rollup_config: RollupConfig =
         from_toml_path(&rollup_config_path).context("Failed to read rollup configuration")?;
-    let rpc_config = rollup_config.rpc_config;
+    let rpc_config = &rollup_config.rpc_config;
     let address = SocketAddr::new(rpc_config.bind_host.parse()?, rpc_config.bind_port);
 
     // Initializing logging
@@ -86,8 +194,22 @@ async fn main() -> Result<(), anyhow::Error> {
         .map_err(|_err| eprintln!("Unable to set global default subscriber"))
         .expect("Cannot fail to set subscriber");
 
+    if let Some(metrics_config) = &rollup_config.metrics {
+        let metrics_address =
+            SocketAddr::new(metrics_config.bind_host.parse()?, metrics_config.bind_port);
+        tokio::spawn(metrics::start_metrics_server(metrics_address));
+    }
+
+    if let Some(replica) = &rollup_config.replica {
+        return run_replica(replica, &rollup_config, address).await;
+    }
+
     // Initialize the ledger database, which stores blocks, transactions, events, etc.
-    let ledger_db = initialize_ledger(&rollup_config.runner.storage.path);
+    let ledger_db = initialize_ledger(
+        &rollup_config.runner.storage.path,
+        rollup_config.node_mode,
+        &rollup_config.ledger_db,
+    );
 
     // Our state transition function implements the StateTransitionRunner interface,
     // so we use that to initialize the STF
@@ -97,12 +219,23 @@ async fn main() -> Result<(), anyhow::Error> {
     // so we use that to initialize the RPC server.
     let storage = demo_runner.get_storage();
     let is_storage_empty = storage.is_empty();
-    let mut methods = get_rpc_methods(storage);
-    let ledger_rpc_module =
-        ledger_rpc::get_ledger_rpc::<DemoBatchReceipt, DemoTxReceipt>(ledger_db.clone());
-    methods
-        .merge(ledger_rpc_module)
-        .expect("Failed to merge rpc modules");
+
+    // The state is committed before the ledger, so a crash in between leaves the state ahead of the ledger.
+    // Bring it back to the version recorded by the last slot in the ledger; the slots after it are re-executed below.
+    // If no slot was committed yet, only the genesis state (the first version) is kept.
+    if !is_storage_empty {
+        let last_state_version = match ledger_db.get_last_state_version()? {
+            Some(version) => version,
+            None if ledger_db.get_next_items_numbers().slot_number == 1 => 1,
+            None => anyhow::bail!(
+                "The last slot in the ledger has no state version, refusing to roll the state back to genesis"
+            ),
+        };
+        info!("Recovering state at version {}", last_state_version);
+        storage.rollback_to(last_state_version)?;
+    }
+
+    let methods = get_all_rpc_methods(&ledger_db, storage.clone());
 
     let _handle = tokio::spawn(async move {
         start_rpc_server(methods, address).await;
@@ -131,18 +264,15 @@ async fn main() -> Result<(), anyhow::Error> {
         debug!("Chain is already initialized. Skipping initialization.");
     }
 
-    // HACK: Tell the rollup that you're running an empty DA layer block so that it will return the latest state root.
-    // This will be removed shortly.
-    demo.begin_slot(Default::default());
-    let (prev_state_root, _) = demo.end_slot();
-    let mut prev_state_root = prev_state_root.0;
-
     // Start the main rollup loop
     let item_numbers = ledger_db.get_next_items_numbers();
     let last_slot_processed_before_shutdown = item_numbers.slot_number - 1;
     let start_height = rollup_config.start_height + last_slot_processed_before_shutdown;
+    let mut prev_state_root =
+        get_state_root(&ledger_db, &storage, last_slot_processed_before_shutdown)?;
 
-    for height in start_height.. {
+    let mut height = start_height;
+    loop {
         info!(
             "Requesting data for height {} and prev_state_root 0x{}",
             height,
@@ -150,9 +280,49 @@ async fn main() -> Result<(), anyhow::Error> {
         );
 
         // Fetch the relevant subset of the next Celestia block
-        let filtered_block = da_service.get_finalized_at(height).await?;
+        let filtered_block = if rollup_config.soft_confirmations {
+            da_service.get_block_at(height).await?
+        } else {
+            da_service.get_finalized_at(height).await?
+        };
         let header = filtered_block.header();
 
+        // Blocks which aren't final may be reorged. If the new block doesn't build on the last executed one,
+        // rewind the ledger and the state to the last block both chains have in common, and execute the new fork from there.
+        let last_slot_number = height - rollup_config.start_height;
+        if rollup_config.soft_confirmations && last_slot_number > 0 {
+            let last_slot_hash = ledger_db.get_slot_hash(last_slot_number)?;
+            if last_slot_hash.as_ref().map(|hash| &hash[..]) != Some(header.prev_hash().as_ref()) {
+                let fork_slot_number = find_fork_point(
+                    &da_service,
+                    &ledger_db,
+                    rollup_config.start_height,
+                    last_slot_number - 1,
+                )
+                .await?;
+                height = rollup_config.start_height + fork_slot_number;
+                info!("Reorg detected, rewinding to height {}", height);
+
+                // The ledger is rewound first, so that a crash in between is recovered on startup.
+                // Rewinding scans and deletes whole ranges of both dbs, so it runs off the async runtime.
+                let (ledger_db, storage) = (ledger_db.clone(), storage.clone());
+                prev_state_root = tokio::task::spawn_blocking(move || {
+                    let state_version = match ledger_db.rewind_to_slot(fork_slot_number)? {
+                        Some(version) => version,
+                        None if fork_slot_number == 0 => 1,
+                        None => anyhow::bail!(
+                            "Slot {} has no state version, refusing to roll the state back to genesis",
+                            fork_slot_number
+                        ),
+                    };
+                    storage.rollback_to(state_version)?;
+                    get_state_root(&ledger_db, &storage, fork_slot_number)
+                })
+                .await??;
+                continue;
+            }
+        }
+
         // For the demo, we create and verify a proof that the data has been extracted from Celestia correctly.
         // In a production implementation, this logic would only run on the prover node - regular full nodes could
         // simply download the data from Celestia without extracting and checking a merkle proof here,
@@ -163,7 +333,9 @@ async fn main() -> Result<(), anyhow::Error> {
             .verify_relevant_tx_list(header, &blob_txs, inclusion_proof, completeness_proof)
             .is_ok());
         info!("Received {} blobs", blob_txs.len());
+        metrics::BLOBS_PER_SLOT.observe(blob_txs.len() as f64);
 
+        let slot_timer = metrics::SLOT_PROCESSING_LATENCY_SECONDS.start_timer();
         demo.begin_slot(Default::default());
         let mut data_to_commit = SlotCommit::new(filtered_block);
         for blob in blob_txs.clone() {
@@ -171,12 +343,17 @@ async fn main() -> Result<(), anyhow::Error> {
             info!("receipts: {:?}", receipts);
             data_to_commit.add_batch(receipts);
         }
-        let (next_state_root, _witness) = demo.end_slot();
+        let (next_state_root, witness) = demo.end_slot();
+        data_to_commit.set_state_version(storage.latest_version());
+        let serialized_witness = bincode::serialize(&witness)?;
+        metrics::WITNESS_SIZE_BYTES.observe(serialized_witness.len() as f64);
+        let witness_hash = Sha256::digest(serialized_witness).into();
+        data_to_commit.set_state_root(next_state_root.0, witness_hash);
 
         // Store the resulting receipts in the ledger database
         ledger_db.commit_slot(data_to_commit)?;
-        prev_state_root = next_state_root.0;
+        slot_timer.observe_duration();
+        prev_state_root = next_state_root.0.to_vec();
+        height += 1;
     }
-
-    Ok(())
 }
@@ -0,0 +1,97 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use once_cell::sync::Lazy;
+use prometheus::{
+    exponential_buckets, register_histogram, Encoder, Histogram, TextEncoder, TEXT_FORMAT,
+};
+use tracing::error;
+
+pub static SLOT_PROCESSING_LATENCY_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "sov_rollup_slot_processing_latency_seconds",
+        // metric description
+        "Time to execute a slot and commit it to the ledger, in seconds",
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static BLOBS_PER_SLOT: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "sov_rollup_blobs_per_slot",
+        // metric description
+        "Number of rollup blobs in each slot",
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 12).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static WITNESS_SIZE_BYTES: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "sov_rollup_witness_size_bytes",
+        // metric description
+        "Size of the serialized witness of each slot, in bytes",
+        exponential_buckets(/*start=*/ 1024.0, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
+/// Serves the metrics of the default prometheus registry over HTTP at `/metrics`.
+pub async fn start_metrics_server(address: SocketAddr) {
+    let make_service =
+        make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(serve_metrics)) });
+    if let Err(e) = Server::bind(&address).serve(make_service).await {
+        error!("Metrics server failed: {}", e);
+    }
+}
+
+async fn serve_metrics(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    if request.uri().path() != "/metrics" {
+        return Ok(with_status(StatusCode::NOT_FOUND));
+    }
+
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        error!("Failed to encode metrics: {}", e);
+        return Ok(with_status(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+    let mut response = Response::new(Body::from(buffer));
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(TEXT_FORMAT));
+    Ok(response)
+}
+
+fn with_status(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_serve_metrics() {
+        BLOBS_PER_SLOT.observe(3.0);
+
+        let request = Request::get("/metrics").body(Body::empty()).unwrap();
+        let response = serve_metrics(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("sov_rollup_blobs_per_slot_count"));
+
+        let request = Request::get("/other").body(Body::empty()).unwrap();
+        let response = serve_metrics(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
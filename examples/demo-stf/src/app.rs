@@ -1,5 +1,7 @@
 #[cfg(feature = "native")]
 use crate::runner_config::Config;
+#[cfg(feature = "native")]
+pub use crate::runtime::get_rpc_methods;
 use crate::runtime::Runtime;
 #[cfg(feature = "native")]
 pub use sov_modules_api::default_context::DefaultContext;
@@ -28,16 +30,6 @@ use sov_state::ZkStorage;
 pub struct DemoAppRunner<C: Context, Vm: Zkvm>(pub DemoApp<C, Vm>);
 pub type ZkAppRunner<Vm> = DemoAppRunner<ZkDefaultContext, Vm>;
 
-#[cfg(feature = "native")]
-use sov_bank::query::{BankRpcImpl, BankRpcServer};
-#[cfg(feature = "native")]
-use sov_election::query::{ElectionRpcImpl, ElectionRpcServer};
-#[cfg(feature = "native")]
-use sov_value_setter::query::{ValueSetterRpcImpl, ValueSetterRpcServer};
-
-#[cfg(feature = "native")]
-use sov_modules_macros::expose_rpc;
-
 #[cfg(feature = "native")]
 pub type NativeAppRunner<Vm> = DemoAppRunner<DefaultContext, Vm>;
 
@@ -49,7 +41,6 @@ pub type DemoBatchReceipt = SequencerOutcome;
 pub type DemoTxReceipt = TxEffect;
 
 #[cfg(feature = "native")]
-#[expose_rpc((Bank<DefaultContext>,Election<DefaultContext>,ValueSetter<DefaultContext>))]
 impl<Vm: Zkvm> StateTransitionRunner<ProverConfig, Vm> for DemoAppRunner<DefaultContext, Vm> {
     type RuntimeConfig = Config;
     type Inner = DemoApp<DefaultContext, Vm>;
@@ -58,7 +49,11 @@ impl<Vm: Zkvm> StateTransitionRunner<ProverConfig, Vm> for DemoAppRunner<Default
         let runtime = Runtime::new();
         let storage = ProverStorage::with_config(runtime_config.storage)
             .expect("Failed to open prover storage");
-        let app = AppTemplate::new(storage, runtime);
+        let mut app =
+            AppTemplate::new(storage, runtime).with_tx_body_policy(runtime_config.tx_body_policy);
+        if runtime_config.parallel_execution {
+            app = app.with_parallel_execution();
+        }
         Self(app)
     }
 
@@ -99,3 +94,15 @@ impl<Vm: Zkvm> RpcRunner for DemoAppRunner<DefaultContext, Vm> {
         self.inner().current_storage.clone()
     }
 }
+
+/// Decodes a transaction body saved in the ledger (see [`sov_modules_stf_template::TxBodyPolicy`]) into the JSON
+/// representation of its call message, of the form `{"<module name>": <call message>}`.
+#[cfg(feature = "native")]
+pub fn decode_tx_body(body: &[u8]) -> anyhow::Result<serde_json::Value> {
+    use borsh::BorshDeserialize;
+    use sov_modules_api::{transaction::Transaction, DispatchCall};
+
+    let tx = Transaction::<DefaultContext>::deserialize_reader(&mut &body[..])?;
+    let call = Runtime::<DefaultContext>::decode_call(tx.runtime_msg())?;
+    Ok(serde_json::to_value(call)?)
+}
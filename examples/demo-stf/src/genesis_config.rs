@@ -14,6 +14,8 @@ pub const DEMO_SEQUENCER_DA_ADDRESS: [u8; 32] = [1; 32];
 pub const LOCKED_AMOUNT: u64 = 200;
 pub const DEMO_SEQ_PUB_KEY_STR: &str = "seq_pub_key";
 pub const DEMO_TOKEN_NAME: &str = "sov-demo-token";
+pub const DEMO_KEY_ROTATION_DELAY: u64 = 10;
+pub const DEMO_CHAIN_ID: u64 = 1;
 
 pub fn create_demo_genesis_config<C: Context>(
     initial_sequencer_balance: u64,
@@ -28,22 +30,14 @@ pub fn create_demo_genesis_config<C: Context>(
     };
 
     let bank_config = sov_bank::BankConfig {
-        tokens: vec![token_config],
+        native_token: Some(token_config),
+        tokens: vec![],
     };
 
-    let token_address = sov_bank::create_token_address::<C>(
-        &bank_config.tokens[0].token_name,
-        &sov_bank::genesis::DEPLOYER,
-        sov_bank::genesis::SALT,
-    );
-
     let sequencer_config = sov_sequencer_registry::SequencerConfig {
         seq_rollup_address: sequencer_address,
         seq_da_address: sequencer_da_address,
-        coins_to_lock: sov_bank::Coins {
-            amount: LOCKED_AMOUNT,
-            token_address,
-        },
+        amount_to_lock: LOCKED_AMOUNT,
     };
 
     let value_setter_config = ValueSetterConfig {
@@ -59,7 +53,11 @@ pub fn create_demo_genesis_config<C: Context>(
         bank_config,
         election_config,
         value_setter_config,
-        sov_accounts::AccountConfig { pub_keys: vec![] },
+        sov_accounts::AccountConfig {
+            pub_keys: vec![],
+            key_rotation_delay: DEMO_KEY_ROTATION_DELAY,
+            chain_id: DEMO_CHAIN_ID,
+        },
     )
 }
 
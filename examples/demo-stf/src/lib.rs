@@ -1,7 +1,6 @@
 pub mod app;
 #[cfg(feature = "native")]
 pub mod genesis_config;
-pub mod hooks_impl;
 #[cfg(feature = "native")]
 pub mod runner_config;
 pub mod runtime;
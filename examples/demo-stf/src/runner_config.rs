@@ -1,5 +1,6 @@
 use serde::de::DeserializeOwned;
 pub use sov_modules_api::default_context::DefaultContext;
+pub use sov_modules_stf_template::TxBodyPolicy;
 pub use sov_state::config::Config as StorageConfig;
 use std::fs::File;
 use std::io::Read;
@@ -20,6 +21,12 @@ pub fn from_toml_path<P: AsRef<Path>, R: DeserializeOwned>(path: P) -> anyhow::R
 #[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Config {
     pub storage: StorageConfig,
+    /// Which transaction bodies are saved in the ledger. Defaults to none.
+    #[serde(default)]
+    pub tx_body_policy: TxBodyPolicy,
+    /// Execute the transactions of each batch optimistically in parallel. Doesn't change the state roots or the receipts.
+    #[serde(default)]
+    pub parallel_execution: bool,
 }
 
 #[cfg(test)]
@@ -48,11 +55,28 @@ mod tests {
         let expected = Config {
             storage: StorageConfig {
                 path: PathBuf::from("/tmp"),
+                rocksdb: Default::default(),
             },
+            tx_body_policy: TxBodyPolicy::Never,
+            parallel_execution: false,
         };
         assert_eq!(config, expected);
     }
 
+    #[test]
+    fn test_tx_body_policy() {
+        let config = r#"
+            tx_body_policy = "always"
+            [storage]
+            path = "/tmp"
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: Config = from_toml_path(config_file.path()).unwrap();
+        assert_eq!(config.tx_body_policy, TxBodyPolicy::Always);
+    }
+
     #[test]
     fn test_incorrect_path() {
         // Not closed quote
@@ -1,9 +1,21 @@
 #[cfg(feature = "native")]
+use sov_bank::query::{BankRpcImpl, BankRpcServer};
+#[cfg(feature = "native")]
+use sov_election::query::{ElectionRpcImpl, ElectionRpcServer};
+#[cfg(feature = "native")]
 pub use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::Context;
 #[cfg(feature = "native")]
-use sov_modules_macros::cli_parser;
-use sov_modules_macros::{DispatchCall, Genesis, MessageCodec};
+use sov_modules_macros::{cli_parser, expose_rpc};
+use sov_modules_macros::{
+    ApplyBlobHooks, DefaultRuntime, DispatchCall, Genesis, MessageCodec, TxHooks,
+};
+use sov_modules_stf_template::SequencerOutcome;
+#[cfg(feature = "native")]
+use sov_value_setter::query::{ValueSetterRpcImpl, ValueSetterRpcServer};
+
+/// The blob height at which the modules of the runtime are migrated to their current schema version.
+pub const MIGRATION_HEIGHT: u64 = 1;
 
 /// The Rollup entrypoint.
 ///
@@ -36,36 +48,54 @@ use sov_modules_macros::{DispatchCall, Genesis, MessageCodec};
 /// `#[derive(MessageCodec)` adds deserialization capabilities to the `Runtime` (implements `decode_call` method).
 /// `Runtime::decode_call` accepts serialized call message and returns a type that implements the `DispatchCall` trait.
 ///  The `DispatchCall` implementation (derived by a macro) forwards the message to the appropriate module and executes its `call` method.
+/// With the `native` feature, the decoded messages can also be serialized to JSON as `{"<module name>": <call message>}`,
+/// which is used to display the transactions saved in the ledger (see `app::decode_tx_body`).
 ///
 /// Similar mechanism works for queries with the difference that queries are submitted by users directly to the rollup node
-/// instead of going through the DA layer.
+/// instead of going through the DA layer. `#[expose_rpc]` generates `get_rpc_methods`, which serves the queries
+/// of the modules marked with `#[rpc]`.
+///
+/// The remaining glue code is derived as well:
+/// - `#[derive(DefaultRuntime)]` generates `Runtime::new`.
+/// - `#[derive(TxHooks)]` forwards the transaction hooks to the module marked with `#[tx_hooks]`.
+/// - `#[derive(ApplyBlobHooks)]` forwards the blob hooks to the modules marked with `#[blob_hooks]`, in the order of the fields.
+///   The outcome of the blob is mapped to the blob result of each module by the function given to `#[blob_hooks]`.
+///   Before the hooks, the modules whose state is older than their `SCHEMA_VERSION` are migrated, once the rollup
+///   reaches [`MIGRATION_HEIGHT`].
 
 #[cfg_attr(feature = "native", cli_parser(DefaultContext))]
-#[derive(Genesis, DispatchCall, MessageCodec)]
+#[cfg_attr(feature = "native", expose_rpc(DefaultContext))]
+#[derive(Genesis, DispatchCall, MessageCodec, DefaultRuntime, TxHooks, ApplyBlobHooks)]
 #[serialization(borsh::BorshDeserialize, borsh::BorshSerialize)]
+#[cfg_attr(
+    feature = "native",
+    serialization(serde::Serialize, serde::Deserialize)
+)]
+#[blob_result(SequencerOutcome)]
+#[migration_height(MIGRATION_HEIGHT)]
 pub struct Runtime<C: Context> {
+    #[blob_hooks(sequencer_reward)]
     pub sequencer: sov_sequencer_registry::Sequencer<C>,
 
+    #[rpc]
     pub bank: sov_bank::Bank<C>,
 
+    #[rpc]
     pub election: sov_election::Election<C>,
 
+    #[rpc]
     pub value_setter: sov_value_setter::ValueSetter<C>,
 
+    #[tx_hooks]
+    #[blob_hooks(|_: &SequencerOutcome| ())]
     pub accounts: sov_accounts::Accounts<C>,
 }
 
-// TODO add macro to generate the following code:
-//      https://github.com/Sovereign-Labs/sovereign/issues/309
-impl<C: Context> Runtime<C> {
-    pub(crate) fn new() -> Self {
-        use sov_modules_api::ModuleInfo;
-        Self {
-            sequencer: sov_sequencer_registry::Sequencer::new(),
-            bank: sov_bank::Bank::new(),
-            election: sov_election::Election::new(),
-            value_setter: sov_value_setter::ValueSetter::new(),
-            accounts: sov_accounts::Accounts::new(),
-        }
+/// The blob hooks of the sequencer registry only get the reward of the sequencer, which is `0` when the sequencer
+/// was slashed or its blob was ignored.
+fn sequencer_reward(outcome: &SequencerOutcome) -> u64 {
+    match outcome {
+        SequencerOutcome::Rewarded(reward) => *reward,
+        SequencerOutcome::Slashed(_) | SequencerOutcome::Ignored => 0,
     }
 }
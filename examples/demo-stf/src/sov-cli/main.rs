@@ -8,6 +8,7 @@ use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
+use demo_stf::genesis_config::DEMO_CHAIN_ID;
 use demo_stf::runtime::cmd_parser;
 
 use sov_modules_api::{
@@ -43,6 +44,11 @@ enum Commands {
         call_data_path: String,
         /// Nonce for the transaction
         nonce: u64,
+        /// Nonce lane for the transaction. Lanes are sequenced independently,
+        /// transactions without a lane use the main nonce of the account.
+        /// An account can use at most 16 lanes over its lifetime, lanes are never closed
+        #[clap(long)]
+        nonce_lane: Option<u64>,
         /// Output file format. borsh and hex are supported
         #[clap(long, default_value = "hex")]
         format: String,
@@ -81,6 +87,25 @@ enum UtilCommands {
         /// Folder to store the new private key json file. The filename is auto-generated
         priv_key_path: String,
     },
+    /// Create the call data of an Accounts UpdatePublicKey message,
+    /// signed with the new private key
+    UpdatePublicKeyCall {
+        /// Path to the json file containing the new private key of the account
+        new_priv_key_path: String,
+        /// Address of the account (can be obtained using the show-public-key subcommand)
+        account_address: String,
+        /// Nonce of the account in the lane of the transaction when the call is executed.
+        /// For a transaction sent with nonce n, this is n + 1
+        account_nonce: u64,
+        /// Path of the json file to write the call data to
+        call_data_path: String,
+        /// Chain id of the rollup
+        #[clap(long, default_value_t = DEMO_CHAIN_ID)]
+        chain_id: u64,
+        /// Nonce lane of the transaction which sends the call
+        #[clap(long, default_value = "0")]
+        nonce_lane: u64,
+    },
 }
 
 struct SerializedTx {
@@ -124,13 +149,22 @@ impl SerializedTx {
         sender_priv_key_path: P,
         module_name: &str,
         call_data_path: P,
+        nonce_lane: Option<u64>,
         nonce: u64,
     ) -> anyhow::Result<SerializedTx> {
         let sender_priv_key = Self::deserialize_priv_key(sender_priv_key_path)?;
         let sender_address = sender_priv_key.pub_key().to_address();
         let message = Self::serialize_call_message(module_name, call_data_path)?;
 
-        let tx = Transaction::<C>::new_signed_tx(&sender_priv_key, message, nonce);
+        let tx = match nonce_lane {
+            Some(nonce_lane) => Transaction::<C>::new_signed_tx_in_lane(
+                &sender_priv_key,
+                message,
+                nonce_lane,
+                nonce,
+            ),
+            None => Transaction::<C>::new_signed_tx(&sender_priv_key, message, nonce),
+        };
 
         Ok(SerializedTx {
             raw: RawTx {
@@ -140,6 +174,27 @@ impl SerializedTx {
         })
     }
 
+    fn update_public_key_call<P: AsRef<Path>>(
+        new_priv_key_path: P,
+        account_address: &Address,
+        nonce_lane: u64,
+        account_nonce: u64,
+        chain_id: u64,
+    ) -> anyhow::Result<String> {
+        let new_priv_key = Self::deserialize_priv_key(new_priv_key_path)?;
+        let msg = sov_accounts::call::update_account_msg::<C>(
+            account_address,
+            nonce_lane,
+            account_nonce,
+            chain_id,
+        );
+        let call = sov_accounts::call::CallMessage::<C>::UpdatePublicKey(
+            new_priv_key.pub_key(),
+            new_priv_key.sign(msg),
+        );
+        Ok(serde_json::to_string(&call)?)
+    }
+
     fn deserialize_priv_key<P: AsRef<Path>>(
         sender_priv_key_path: P,
     ) -> anyhow::Result<DefaultPrivateKey> {
@@ -180,11 +235,17 @@ pub fn main() {
             module_name,
             call_data_path,
             nonce,
+            nonce_lane,
             format,
         } => {
-            let serialized =
-                SerializedTx::new(&sender_priv_key_path, &module_name, &call_data_path, nonce)
-                    .unwrap_or_else(|e| panic!("Call message serialization error: {}", e));
+            let serialized = SerializedTx::new(
+                &sender_priv_key_path,
+                &module_name,
+                &call_data_path,
+                nonce_lane,
+                nonce,
+            )
+            .unwrap_or_else(|e| panic!("Call message serialization error: {}", e));
 
             let mut bin_path = PathBuf::from(call_data_path);
             bin_path.set_extension("dat");
@@ -225,6 +286,35 @@ pub fn main() {
                 PrivKeyAndAddress::generate_and_save_to_file(priv_key_path.as_ref())
                     .unwrap_or_else(|e| panic!("Create private key error: {}", e));
             }
+
+            UtilCommands::UpdatePublicKeyCall {
+                new_priv_key_path,
+                account_address,
+                account_nonce,
+                call_data_path,
+                chain_id,
+                nonce_lane,
+            } => {
+                let account_address = Address::from(
+                    AddressBech32::try_from(account_address.clone()).unwrap_or_else(|e| {
+                        panic!(
+                            "Failed to derive address from string {}: {}",
+                            account_address, e
+                        )
+                    }),
+                );
+                let call_data = SerializedTx::update_public_key_call(
+                    new_priv_key_path,
+                    &account_address,
+                    nonce_lane,
+                    account_nonce,
+                    chain_id,
+                )
+                .unwrap_or_else(|e| panic!("Update public key call error: {}", e));
+                fs::write(&call_data_path, call_data)
+                    .unwrap_or_else(|e| panic!("Unable to save call data: {}", e));
+                println!("call data written to path: {}", call_data_path);
+            }
         },
     }
 }
@@ -284,7 +374,12 @@ mod test {
 
             let path = path.as_ref().to_path_buf();
             let runner_config = Config {
-                storage: sov_state::config::Config { path },
+                storage: sov_state::config::Config {
+                    path,
+                    rocksdb: Default::default(),
+                },
+                tx_body_policy: Default::default(),
+                parallel_execution: false,
             };
 
             Self {
@@ -316,6 +411,7 @@ mod test {
             make_test_path("token_deployer_private_key.json"),
             "Bank",
             make_test_path("create_token.json"),
+            None,
             0,
         )
         .unwrap();
@@ -324,6 +420,7 @@ mod test {
             make_test_path("minter_private_key.json"),
             "Bank",
             make_test_path("transfer.json"),
+            None,
             0,
         )
         .unwrap();
@@ -332,6 +429,7 @@ mod test {
             make_test_path("minter_private_key.json"),
             "Bank",
             make_test_path("burn.json"),
+            None,
             1,
         )
         .unwrap();
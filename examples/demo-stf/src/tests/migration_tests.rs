@@ -0,0 +1,67 @@
+use sov_election::{call::CallMessage, ADMIN};
+use sov_modules_api::{
+    access::Roles,
+    default_signature::private_key::DefaultPrivateKey,
+    hooks::ApplyBlobHooks,
+    migration::{schema_version, set_schema_version},
+    Context, Genesis, Module, PublicKey, Spec,
+};
+use sov_state::{ProverStorage, StateValue, WorkingSet};
+
+use super::{create_demo_config, C};
+use crate::{
+    genesis_config::{DEMO_SEQUENCER_DA_ADDRESS, LOCKED_AMOUNT},
+    runtime::Runtime,
+};
+
+#[test]
+fn test_runtime_migrates_modules() {
+    let value_setter_admin_private_key = DefaultPrivateKey::generate();
+    let election_admin_private_key = DefaultPrivateKey::generate();
+    let election_admin = election_admin_private_key
+        .pub_key()
+        .to_address::<<C as Spec>::Address>();
+    let config = create_demo_config(
+        LOCKED_AMOUNT + 1,
+        &value_setter_admin_private_key,
+        &election_admin_private_key,
+    );
+
+    let runtime = Runtime::<C>::new();
+    let working_set = &mut WorkingSet::new(ProverStorage::temporary());
+    runtime.genesis(&config, working_set).unwrap();
+    assert_eq!(schema_version(&runtime.election, working_set), 1);
+
+    // Emulate the state of the version `0` of the election module, where the admin was stored as an address.
+    let roles = Roles::<C>::new(
+        sov_modules_api::Prefix::new_storage("sov_election", "Election", "roles").into(),
+    );
+    let admin = StateValue::<<C as Spec>::Address>::new(
+        sov_modules_api::Prefix::new_storage("sov_election", "Election", "admin").into(),
+    );
+    roles.revoke(ADMIN, &election_admin, working_set);
+    admin.set(&election_admin, working_set);
+    set_schema_version(&runtime.election, 0, working_set);
+
+    let set_candidates = || CallMessage::SetCandidates {
+        names: vec!["candidate_1".to_owned(), "candidate_2".to_owned()],
+    };
+    let context = C::new(election_admin.clone());
+    assert!(runtime
+        .election
+        .call(set_candidates(), &context, working_set)
+        .is_err());
+
+    // The first blob reaches the migration height of the runtime.
+    runtime
+        .begin_blob_hook(&DEMO_SEQUENCER_DA_ADDRESS, &[], working_set)
+        .unwrap();
+
+    assert_eq!(schema_version(&runtime.election, working_set), 1);
+    assert!(roles.has_role(ADMIN, &election_admin, working_set));
+    assert!(admin.get(working_set).is_none());
+    runtime
+        .election
+        .call(set_candidates(), &context, working_set)
+        .unwrap();
+}
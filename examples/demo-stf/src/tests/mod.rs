@@ -17,6 +17,7 @@ use crate::{
 };
 
 mod data_generation;
+mod migration_tests;
 mod stf_tests;
 mod tx_revert_tests;
 pub(crate) type C = DefaultContext;
@@ -1,6 +1,7 @@
 #[cfg(test)]
 pub mod test {
     use crate::{
+        app::decode_tx_body,
         genesis_config::{DEMO_SEQUENCER_DA_ADDRESS, LOCKED_AMOUNT},
         runtime::Runtime,
         tests::{
@@ -11,7 +12,7 @@ pub mod test {
     use sov_modules_api::{
         default_context::DefaultContext, default_signature::private_key::DefaultPrivateKey,
     };
-    use sov_modules_stf_template::{Batch, SequencerOutcome};
+    use sov_modules_stf_template::{Batch, SequencerOutcome, TxBodyPolicy};
     use sov_rollup_interface::{mocks::MockZkvm, stf::StateTransitionFunction};
     use sov_state::{ProverStorage, WorkingSet};
 
@@ -208,4 +209,95 @@ pub mod test {
         // Assert that there are no events
         assert!(!has_tx_events(&apply_blob_outcome));
     }
+
+    #[test]
+    fn test_parallel_execution() {
+        let value_setter_admin_private_key = DefaultPrivateKey::generate();
+        let election_admin_private_key = DefaultPrivateKey::generate();
+        let create_config = || {
+            create_demo_config(
+                LOCKED_AMOUNT + 1,
+                &value_setter_admin_private_key,
+                &election_admin_private_key,
+            )
+        };
+        let sequential_config = create_config();
+        let parallel_config = create_config();
+        // The votes and the nonces of the senders make most of the transactions conflict with each other.
+        let txs = simulate_da(value_setter_admin_private_key, election_admin_private_key);
+
+        let sequential_path = sov_schema_db::temppath::TempPath::new();
+        let parallel_path = sov_schema_db::temppath::TempPath::new();
+        let mut sequential_demo = create_new_demo(&sequential_path);
+        let mut parallel_demo = create_new_demo(&parallel_path).with_parallel_execution();
+        StateTransitionFunction::<MockZkvm>::init_chain(&mut sequential_demo, sequential_config);
+        StateTransitionFunction::<MockZkvm>::init_chain(&mut parallel_demo, parallel_config);
+
+        let mut outputs = Vec::new();
+        for demo in [&mut sequential_demo, &mut parallel_demo] {
+            StateTransitionFunction::<MockZkvm>::begin_slot(demo, Default::default());
+            let apply_blob_outcome = StateTransitionFunction::<MockZkvm>::apply_blob(
+                demo,
+                new_test_blob(Batch { txs: txs.clone() }, &DEMO_SEQUENCER_DA_ADDRESS),
+                None,
+            );
+            let (root, witness) = StateTransitionFunction::<MockZkvm>::end_slot(demo);
+            outputs.push((
+                root,
+                serde_json::to_value(apply_blob_outcome).unwrap(),
+                serde_json::to_value(witness).unwrap(),
+            ));
+        }
+        assert_eq!(outputs[0], outputs[1]);
+
+        let runtime = &mut Runtime::<DefaultContext>::new();
+        let mut working_set = WorkingSet::new(parallel_demo.current_storage.clone());
+        let resp = runtime.value_setter.query_value(&mut working_set);
+        assert_eq!(resp, sov_value_setter::query::Response { value: Some(33) });
+    }
+
+    #[test]
+    fn test_tx_bodies_saved() {
+        let path = sov_schema_db::temppath::TempPath::new();
+        let mut demo = create_new_demo(&path).with_tx_body_policy(TxBodyPolicy::Always);
+
+        let value_setter_admin_private_key = DefaultPrivateKey::generate();
+        let election_admin_private_key = DefaultPrivateKey::generate();
+
+        let config = create_demo_config(
+            LOCKED_AMOUNT + 1,
+            &value_setter_admin_private_key,
+            &election_admin_private_key,
+        );
+
+        StateTransitionFunction::<MockZkvm>::init_chain(&mut demo, config);
+        StateTransitionFunction::<MockZkvm>::begin_slot(&mut demo, Default::default());
+
+        let txs = simulate_da(value_setter_admin_private_key, election_admin_private_key);
+
+        let apply_blob_outcome = StateTransitionFunction::<MockZkvm>::apply_blob(
+            &mut demo,
+            new_test_blob(Batch { txs: txs.clone() }, &DEMO_SEQUENCER_DA_ADDRESS),
+            None,
+        );
+        StateTransitionFunction::<MockZkvm>::end_slot(&mut demo);
+
+        assert_eq!(apply_blob_outcome.tx_receipts.len(), txs.len());
+        for (receipt, tx) in apply_blob_outcome.tx_receipts.iter().zip(txs) {
+            assert_eq!(receipt.body_to_save.as_ref(), Some(&tx.data));
+        }
+
+        // The last transaction sets the value of the value setter module.
+        let body = apply_blob_outcome
+            .tx_receipts
+            .last()
+            .unwrap()
+            .body_to_save
+            .as_ref();
+        let decoded = decode_tx_body(body.unwrap()).unwrap();
+        assert_eq!(
+            decoded,
+            serde_json::json!({ "value_setter": { "SetValue": 33 } })
+        );
+    }
 }
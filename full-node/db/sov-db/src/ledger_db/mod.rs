@@ -9,25 +9,35 @@ use sov_rollup_interface::{
     services::da::SlotData,
     stf::{BatchReceipt, Event},
 };
-use sov_schema_db::{Schema, DB};
+use sov_schema_db::{Schema, SchemaBatch, DB};
+use tokio::sync::broadcast;
 
 use crate::{
-    rocks_db_config::gen_rocksdb_options,
+    rocks_db_config::{
+        gen_cf_descriptors, gen_rocksdb_options, gen_secondary_rocksdb_options, RocksdbConfig,
+    },
     schema::{
         tables::{
-            BatchByHash, BatchByNumber, EventByKey, EventByNumber, SlotByHash, SlotByNumber,
-            TxByHash, TxByNumber, LEDGER_TABLES,
+            BatchByHash, BatchByNumber, EventByKey, EventByNumber, LastPrunedSlot,
+            LedgerFormatVersion, SlotByHash, SlotByNumber, TxByHash, TxByNumber, LEDGER_TABLES,
         },
         types::{
-            split_tx_for_storage, BatchNumber, EventNumber, SlotNumber, StoredBatch, StoredSlot,
-            StoredTransaction, TxNumber,
+            split_tx_for_storage, BatchNumber, DbHash, EventNumber, SlotNumber, StoredBatch,
+            StoredSlot, StoredStateRoot, StoredTransaction, TxNumber,
         },
     },
 };
 
+mod pruning;
 mod rpc;
 
+pub use pruning::{NodeMode, PrunedError};
+
 const LEDGER_DB_PATH_SUFFIX: &str = "ledger";
+/// The version of the on-disk format of the ledger. Bump it whenever the encoding of a key or a stored value changes.
+const LEDGER_FORMAT_VERSION: u64 = 1;
+/// The number of slot notifications buffered for each subscriber. Subscribers which fall further behind miss notifications.
+const SLOT_NOTIFICATIONS_CAPACITY: usize = 64;
 
 #[derive(Clone)]
 /// A database which stores the ledger history (slots, transactions, events, etc).
@@ -38,9 +48,19 @@ pub struct LedgerDB {
     /// requires transactions to be executed before being committed.
     db: Arc<DB>,
     next_item_numbers: Arc<Mutex<ItemNumbers>>,
+    slot_notifications: broadcast::Sender<SlotNotification>,
+    mode: NodeMode,
+}
+
+/// A notification sent to the subscribers of the ledger every time a slot is committed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlotNotification {
+    pub slot_number: u64,
+    /// The numbers of the transactions included in the slot.
+    pub tx_range: std::ops::Range<u64>,
 }
 
-#[derive(Default, Clone, Debug)]
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
 pub struct ItemNumbers {
     pub slot_number: u64,
     pub batch_number: u64,
@@ -51,8 +71,8 @@ pub struct ItemNumbers {
 pub struct SlotCommit<S: SlotData, B, T> {
     slot_data: S,
     batch_receipts: Vec<BatchReceipt<B, T>>,
-    num_txs: usize,
-    num_events: usize,
+    state_version: Option<u64>,
+    state_root: Option<StoredStateRoot>,
 }
 
 impl<S: SlotData, B, T> SlotCommit<S, B, T> {
@@ -60,46 +80,132 @@ impl<S: SlotData, B, T> SlotCommit<S, B, T> {
         Self {
             slot_data,
             batch_receipts: vec![],
-            num_txs: 0,
-            num_events: 0,
+            state_version: None,
+            state_root: None,
         }
     }
 }
 
 impl<S: SlotData, B, T> SlotCommit<S, B, T> {
     pub fn add_batch(&mut self, batch: BatchReceipt<B, T>) {
-        self.num_txs += batch.tx_receipts.len();
-        let events_this_batch: usize = batch.tx_receipts.iter().map(|r| r.events.len()).sum();
         self.batch_receipts.push(batch);
-        self.num_events += events_this_batch;
+    }
+
+    /// Records the version of the state db after the slot was executed. The state must be committed
+    /// before the slot, so that the node can roll the state back to the last committed slot after a crash.
+    /// See [`LedgerDB::get_last_state_version`].
+    pub fn set_state_version(&mut self, version: u64) {
+        self.state_version = Some(version);
+    }
+
+    /// Records the state root after the slot was executed, and the hash of the witness produced while executing it.
+    /// See [`sov_rollup_interface::rpc::LedgerRpcProvider::get_state_root`].
+    pub fn set_state_root(&mut self, state_root: impl AsRef<[u8]>, witness_hash: DbHash) {
+        self.state_root = Some(StoredStateRoot {
+            root: state_root.as_ref().to_vec().into(),
+            witness_hash,
+        });
     }
 }
 
 impl LedgerDB {
+    /// Opens the ledger of an archive node, see [`NodeMode::Archive`].
     pub fn with_path(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        Self::with_path_and_mode(path, NodeMode::Archive)
+    }
+
+    /// Opens the ledger, and starts pruning it in the background if the node is in [`NodeMode::Pruned`].
+    pub fn with_path_and_mode(
+        path: impl AsRef<Path>,
+        mode: NodeMode,
+    ) -> Result<Self, anyhow::Error> {
+        Self::with_config(path, mode, &RocksdbConfig::default())
+    }
+
+    /// Same as [`LedgerDB::with_path_and_mode`], with the given rocksdb tuning.
+    pub fn with_config(
+        path: impl AsRef<Path>,
+        mode: NodeMode,
+        rocksdb_config: &RocksdbConfig,
+    ) -> Result<Self, anyhow::Error> {
+        if let NodeMode::Pruned { keep_slots } = mode {
+            anyhow::ensure!(keep_slots > 0, "A pruned node must keep at least one slot");
+        }
         let path = path.as_ref().join(LEDGER_DB_PATH_SUFFIX);
-        let inner = DB::open(
+        let inner = DB::open_cf(
+            &gen_rocksdb_options(rocksdb_config, false),
             path,
             "ledger-db",
-            LEDGER_TABLES.iter().copied(),
-            &gen_rocksdb_options(&Default::default(), false),
+            gen_cf_descriptors(rocksdb_config, LEDGER_TABLES)?,
         )?;
 
-        let next_item_numbers = ItemNumbers {
-            slot_number: Self::last_version_written(&inner, SlotByNumber)?.unwrap_or_default() + 1,
-            batch_number: Self::last_version_written(&inner, BatchByNumber)?.unwrap_or_default()
-                + 1,
-            tx_number: Self::last_version_written(&inner, TxByNumber)?.unwrap_or_default() + 1,
-            event_number: Self::last_version_written(&inner, EventByNumber)?.unwrap_or_default()
-                + 1,
+        Self::check_format_version(&inner, true)?;
+        let next_item_numbers = Self::recover_item_numbers(&inner)?;
+        let (slot_notifications, _) = broadcast::channel(SLOT_NOTIFICATIONS_CAPACITY);
+
+        let ledger_db = Self {
+            db: Arc::new(inner),
+            next_item_numbers: Arc::new(Mutex::new(next_item_numbers)),
+            slot_notifications,
+            mode,
         };
+        if let NodeMode::Pruned { keep_slots } = mode {
+            ledger_db.spawn_pruner(keep_slots)?;
+        }
+        Ok(ledger_db)
+    }
+
+    /// Opens the ledger at `primary_path` as a read-only secondary, which keeps its own files at `secondary_path`.
+    /// The secondary only sees the slots committed by the primary once [`LedgerDB::catch_up_with_primary`] is called.
+    /// Its `mode` should be the one of the primary, so that data outside of the window kept by the primary is reported
+    /// as pruned.
+    pub fn secondary_with_path(
+        primary_path: impl AsRef<Path>,
+        secondary_path: impl AsRef<Path>,
+        mode: NodeMode,
+        rocksdb_config: &RocksdbConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let inner = DB::open_cf_as_secondary(
+            &gen_secondary_rocksdb_options(rocksdb_config),
+            primary_path.as_ref().join(LEDGER_DB_PATH_SUFFIX),
+            secondary_path.as_ref().join(LEDGER_DB_PATH_SUFFIX),
+            "ledger-db",
+            LEDGER_TABLES.to_vec(),
+        )?;
+
+        Self::check_format_version(&inner, false)?;
+        let next_item_numbers = Self::recover_item_numbers(&inner)?;
+        let (slot_notifications, _) = broadcast::channel(SLOT_NOTIFICATIONS_CAPACITY);
 
         Ok(Self {
             db: Arc::new(inner),
             next_item_numbers: Arc::new(Mutex::new(next_item_numbers)),
+            slot_notifications,
+            mode,
         })
     }
 
+    /// Catches a secondary ledger up with the slots committed by the primary since it was opened or last caught up,
+    /// and notifies the subscribers of the new slots.
+    pub fn catch_up_with_primary(&self) -> anyhow::Result<()> {
+        let mut next_item_numbers = self.next_item_numbers.lock().unwrap();
+        self.db.try_catch_up_with_primary()?;
+        let caught_up = Self::recover_item_numbers(&self.db)?;
+
+        let mut tx_start = next_item_numbers.tx_number;
+        for slot_number in next_item_numbers.slot_number..caught_up.slot_number {
+            let tx_end = Self::first_items_after_slot(&self.db, slot_number, &caught_up)?.tx_number;
+            // Sending only fails if there are no subscribers.
+            let _ = self.slot_notifications.send(SlotNotification {
+                slot_number,
+                tx_range: tx_start..tx_end,
+            });
+            tx_start = tx_end;
+        }
+        *next_item_numbers = caught_up;
+        Ok(())
+    }
+
     /// A rocksdb instance which stores its data in a tempdir
     #[cfg(any(test, feature = "temp"))]
     pub fn temporary() -> Self {
@@ -111,6 +217,151 @@ impl LedgerDB {
         self.next_item_numbers.lock().unwrap().clone()
     }
 
+    /// Returns a receiver which is notified of every slot committed from now on.
+    pub fn subscribe_slots(&self) -> broadcast::Receiver<SlotNotification> {
+        self.slot_notifications.subscribe()
+    }
+
+    /// Returns the version of the state db recorded with the last committed slot.
+    /// A state db ahead of this version contains the effects of a slot that was never committed
+    /// to the ledger, and has to be rolled back before the slot is executed again.
+    pub fn get_last_state_version(&self) -> anyhow::Result<Option<u64>> {
+        Ok(Self::last_entry::<SlotByNumber, _, _>(&self.db)?
+            .and_then(|(_, slot)| slot.state_version))
+    }
+
+    /// Returns the hash of the da block of the slot `slot_number`, if it is in the ledger.
+    pub fn get_slot_hash(&self, slot_number: u64) -> anyhow::Result<Option<DbHash>> {
+        Ok(self
+            .db
+            .get::<SlotByNumber>(&SlotNumber(slot_number))?
+            .map(|slot| slot.hash))
+    }
+
+    /// Deletes all the slots after `slot_number`, together with their batches, transactions and events,
+    /// in a single atomic write. Used to rewind the ledger to the fork point when the da layer reorgs.
+    ///
+    /// Returns the version of the state db recorded with the slot `slot_number`, which is the version the
+    /// state has to be rolled back to. Passing `0` removes all the slots. Fails if the slot was pruned.
+    pub fn rewind_to_slot(&self, slot_number: u64) -> anyhow::Result<Option<u64>> {
+        let mut next_item_numbers = self.next_item_numbers.lock().unwrap();
+        // The numbers of the items after a pruned slot can't be recovered.
+        if let Some(last_pruned_slot) = self.db.get::<LastPrunedSlot>(&())? {
+            anyhow::ensure!(
+                slot_number >= last_pruned_slot.0,
+                "Can't rewind to slot {}, the ledger was pruned up to slot {}",
+                slot_number,
+                last_pruned_slot.0
+            );
+        }
+        let first = Self::first_items_after_slot(&self.db, slot_number, &next_item_numbers)?;
+        let state_version = self
+            .db
+            .get::<SlotByNumber>(&SlotNumber(slot_number))?
+            .and_then(|slot| slot.state_version);
+
+        let schema_batch = SchemaBatch::new();
+        let mut slots = self.db.iter::<SlotByNumber>()?;
+        slots.seek(&SlotNumber(slot_number + 1))?;
+        for entry in slots {
+            let (number, slot) = entry?;
+            schema_batch.delete::<SlotByNumber>(&number)?;
+            schema_batch.delete::<SlotByHash>(&slot.hash)?;
+        }
+        Self::delete_items(&self.db, &first, &next_item_numbers, &schema_batch)?;
+
+        self.db.write_schemas(schema_batch)?;
+        *next_item_numbers = first;
+
+        Ok(state_version)
+    }
+
+    /// Returns the numbers of the first slot, batch, transaction and event after the slot `slot_number`.
+    /// Passing `0` returns the numbers of the first items of the ledger. The numbers only depend on the slot
+    /// and the items after it, which are kept even if the ledger is pruned.
+    fn first_items_after_slot(
+        db: &DB,
+        slot_number: u64,
+        next_item_numbers: &ItemNumbers,
+    ) -> anyhow::Result<ItemNumbers> {
+        let batch_number = match slot_number {
+            0 => 1,
+            number => {
+                db.get::<SlotByNumber>(&SlotNumber(number))?
+                    .ok_or_else(|| anyhow::anyhow!("Slot {} is not in the ledger", number))?
+                    .batches
+                    .end
+                    .0
+            }
+        };
+        // The items after the slot start where the first batch after it starts. If there is no such
+        // batch (or transaction), all the transactions (or events) in the ledger are before the slot.
+        let tx_number = db
+            .get::<BatchByNumber>(&BatchNumber(batch_number))?
+            .map_or(next_item_numbers.tx_number, |batch| batch.txs.start.0);
+        let event_number = db
+            .get::<TxByNumber>(&TxNumber(tx_number))?
+            .map_or(next_item_numbers.event_number, |tx| tx.events.start.0);
+        Ok(ItemNumbers {
+            slot_number: slot_number + 1,
+            batch_number,
+            tx_number,
+            event_number,
+        })
+    }
+
+    /// Adds the deletion of the batches, transactions and events with numbers from `first` to `end`
+    /// (excluded) to `schema_batch`, together with their entries in the indexes.
+    fn delete_items(
+        db: &DB,
+        first: &ItemNumbers,
+        end: &ItemNumbers,
+        schema_batch: &SchemaBatch,
+    ) -> anyhow::Result<()> {
+        let mut batches = db.iter::<BatchByNumber>()?;
+        batches.seek(&BatchNumber(first.batch_number))?;
+        for entry in batches {
+            let (number, batch) = entry?;
+            if number.0 >= end.batch_number {
+                break;
+            }
+            schema_batch.delete::<BatchByNumber>(&number)?;
+            schema_batch.delete::<BatchByHash>(&batch.hash)?;
+        }
+
+        let mut txs = db.iter::<TxByNumber>()?;
+        txs.seek(&TxNumber(first.tx_number))?;
+        for entry in txs {
+            let (tx_number, tx) = entry?;
+            if tx_number.0 >= end.tx_number {
+                break;
+            }
+            for event_number in tx.events.start.0..tx.events.end.0 {
+                let event_number = EventNumber(event_number);
+                if let Some(event) = db.get::<EventByNumber>(&event_number)? {
+                    schema_batch.delete::<EventByKey>(&(
+                        event.key().clone(),
+                        tx_number,
+                        event_number,
+                    ))?;
+                }
+            }
+            schema_batch.delete::<TxByNumber>(&tx_number)?;
+            schema_batch.delete::<TxByHash>(&tx.hash)?;
+        }
+
+        let mut events = db.iter::<EventByNumber>()?;
+        events.seek(&EventNumber(first.event_number))?;
+        for entry in events {
+            let (number, _) = entry?;
+            if number.0 >= end.event_number {
+                break;
+            }
+            schema_batch.delete::<EventByNumber>(&number)?;
+        }
+        Ok(())
+    }
+
     /// Gets all slots with numbers `range.start` to `range.end`. If `range.end` is outside
     /// the range of the database, the result will smaller than the requested range.
     /// Note that this method blindly preallocates for the requested range, so it should not be exposed
@@ -165,27 +416,34 @@ impl LedgerDB {
         Ok(out)
     }
 
-    fn put_slot(&self, slot: &StoredSlot, slot_number: &SlotNumber) -> Result<(), anyhow::Error> {
-        self.db.put::<SlotByNumber>(slot_number, slot)?;
-        self.db.put::<SlotByHash>(&slot.hash, slot_number)
+    fn put_slot(
+        &self,
+        slot: &StoredSlot,
+        slot_number: &SlotNumber,
+        schema_batch: &SchemaBatch,
+    ) -> Result<(), anyhow::Error> {
+        schema_batch.put::<SlotByNumber>(slot_number, slot)?;
+        schema_batch.put::<SlotByHash>(&slot.hash, slot_number)
     }
 
     fn put_batch(
         &self,
         batch: &StoredBatch,
         batch_number: &BatchNumber,
+        schema_batch: &SchemaBatch,
     ) -> Result<(), anyhow::Error> {
-        self.db.put::<BatchByNumber>(batch_number, batch)?;
-        self.db.put::<BatchByHash>(&batch.hash, batch_number)
+        schema_batch.put::<BatchByNumber>(batch_number, batch)?;
+        schema_batch.put::<BatchByHash>(&batch.hash, batch_number)
     }
 
     fn put_transaction(
         &self,
         tx: &StoredTransaction,
         tx_number: &TxNumber,
+        schema_batch: &SchemaBatch,
     ) -> Result<(), anyhow::Error> {
-        self.db.put::<TxByNumber>(tx_number, tx)?;
-        self.db.put::<TxByHash>(&tx.hash, tx_number)
+        schema_batch.put::<TxByNumber>(tx_number, tx)?;
+        schema_batch.put::<TxByHash>(&tx.hash, tx_number)
     }
 
     fn put_event(
@@ -193,34 +451,26 @@ impl LedgerDB {
         event: &Event,
         event_number: &EventNumber,
         tx_number: TxNumber,
+        schema_batch: &SchemaBatch,
     ) -> Result<(), anyhow::Error> {
-        self.db.put::<EventByNumber>(event_number, event)?;
-        self.db
-            .put::<EventByKey>(&(event.key().clone(), tx_number, *event_number), &())
+        schema_batch.put::<EventByNumber>(event_number, event)?;
+        schema_batch.put::<EventByKey>(&(event.key().clone(), tx_number, *event_number), &())
     }
 
-    /// Commits a slot to the database by inserting its events, transactions, and batches before
-    /// inserting the slot metadata.
+    /// Commits a slot to the database, together with its events, transactions, and batches,
+    /// in a single atomic write.
     pub fn commit_slot<S: SlotData, B: Serialize, T: Serialize>(
         &self,
         data_to_commit: SlotCommit<S, B, T>,
     ) -> Result<(), anyhow::Error> {
-        // TODO: expose a batch API on the db to commit everything atomically
-        // Create a scope to ensure that the lock is released before we commit to the db
-        let mut current_item_numbers = {
-            let mut next_item_numbers = self.next_item_numbers.lock().unwrap();
-            let item_numbers = next_item_numbers.clone();
-            next_item_numbers.slot_number += 1;
-            next_item_numbers.batch_number += data_to_commit.batch_receipts.len() as u64;
-            next_item_numbers.tx_number += data_to_commit.num_txs as u64;
-            next_item_numbers.event_number += data_to_commit.num_events as u64;
-            item_numbers
-            // The lock is released here
-        };
+        // The lock is held until the slot is written, so that the item numbers are only
+        // advanced once the data they refer to is in the db.
+        let mut next_item_numbers = self.next_item_numbers.lock().unwrap();
+        let mut current_item_numbers = next_item_numbers.clone();
+        let schema_batch = SchemaBatch::new();
 
         let first_batch_number = current_item_numbers.batch_number;
         let last_batch_number = first_batch_number + data_to_commit.batch_receipts.len() as u64;
-        // Insert data from "bottom up" to ensure consistency if the application crashes during insertion
         for batch_receipt in data_to_commit.batch_receipts.into_iter() {
             let first_tx_number = current_item_numbers.tx_number;
             let last_tx_number = first_tx_number + batch_receipt.tx_receipts.len() as u64;
@@ -233,10 +483,15 @@ impl LedgerDB {
                         &event,
                         &EventNumber(current_item_numbers.event_number),
                         TxNumber(current_item_numbers.tx_number),
+                        &schema_batch,
                     )?;
                     current_item_numbers.event_number += 1;
                 }
-                self.put_transaction(&tx_to_store, &TxNumber(current_item_numbers.tx_number))?;
+                self.put_transaction(
+                    &tx_to_store,
+                    &TxNumber(current_item_numbers.tx_number),
+                    &schema_batch,
+                )?;
                 current_item_numbers.tx_number += 1;
             }
 
@@ -251,6 +506,7 @@ impl LedgerDB {
             self.put_batch(
                 &batch_to_store,
                 &BatchNumber(current_item_numbers.batch_number),
+                &schema_batch,
             )?;
             current_item_numbers.batch_number += 1;
         }
@@ -258,29 +514,575 @@ impl LedgerDB {
         // Once all batches are inserted, Insert slot
         let slot_to_store = StoredSlot {
             hash: data_to_commit.slot_data.hash(),
-            // TODO: Add a method to the slotdata trait allowing additional data to be stored
-            extra_data: vec![].into(),
+            extra_data: data_to_commit.slot_data.extra_data_for_storage().into(),
             batches: BatchNumber(first_batch_number)..BatchNumber(last_batch_number),
+            state_version: data_to_commit.state_version,
+            state_root: data_to_commit.state_root,
         };
         self.put_slot(
             &slot_to_store,
             &SlotNumber(current_item_numbers.slot_number),
+            &schema_batch,
         )?;
+        current_item_numbers.slot_number += 1;
+
+        // Write the whole slot atomically, a crash can't leave a partially committed slot behind.
+        self.db.write_schemas(schema_batch)?;
+        // Sending only fails if there are no subscribers.
+        let _ = self.slot_notifications.send(SlotNotification {
+            slot_number: next_item_numbers.slot_number,
+            tx_range: next_item_numbers.tx_number..current_item_numbers.tx_number,
+        });
+        *next_item_numbers = current_item_numbers;
 
         Ok(())
     }
 
-    fn last_version_written<T: Schema<Key = U>, U: Into<u64>>(
-        db: &DB,
-        _schema: T,
-    ) -> anyhow::Result<Option<u64>> {
+    /// Computes the next item numbers from the last slot in the database, and checks
+    /// that all the ledger tables end at the same slot.
+    /// Fails if the ledger was written in another format than [`LEDGER_FORMAT_VERSION`]. Ledgers written before
+    /// the format was versioned have no version, and can't be read either: such a ledger has to be removed and synced again.
+    /// An empty ledger opened by the primary is stamped with the current version.
+    fn check_format_version(db: &DB, is_primary: bool) -> anyhow::Result<()> {
+        match db.get::<LedgerFormatVersion>(&())? {
+            Some(version) => anyhow::ensure!(
+                version == LEDGER_FORMAT_VERSION,
+                "The ledger has format version {}, but this node only reads version {}",
+                version,
+                LEDGER_FORMAT_VERSION
+            ),
+            None => {
+                let mut slots = db.iter::<SlotByNumber>()?;
+                slots.seek_to_first();
+                anyhow::ensure!(
+                    slots.next().is_none(),
+                    "The ledger was written by an older version of the node and has to be synced again"
+                );
+                if is_primary {
+                    db.put::<LedgerFormatVersion>(&(), &LEDGER_FORMAT_VERSION)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn recover_item_numbers(db: &DB) -> anyhow::Result<ItemNumbers> {
+        let last_slot = Self::last_entry::<SlotByNumber, _, _>(db)?;
+        let last_batch = Self::last_entry::<BatchByNumber, _, _>(db)?;
+        let last_tx = Self::last_entry::<TxByNumber, _, _>(db)?;
+        let last_event = Self::last_entry::<EventByNumber, _, _>(db)?;
+
+        let next_item_numbers = ItemNumbers {
+            slot_number: last_slot.as_ref().map_or(1, |(number, _)| number.0 + 1),
+            batch_number: last_batch.as_ref().map_or(1, |(number, _)| number.0 + 1),
+            tx_number: last_tx.as_ref().map_or(1, |(number, _)| number.0 + 1),
+            event_number: last_event.as_ref().map_or(1, |(number, _)| number.0 + 1),
+        };
+
+        // Every table must end where the last item of its parent table ends.
+        let expected_batch_number = last_slot.as_ref().map_or(1, |(_, slot)| slot.batches.end.0);
+        let expected_tx_number = last_batch.as_ref().map_or(1, |(_, batch)| batch.txs.end.0);
+        let expected_event_number = last_tx.as_ref().map_or(1, |(_, tx)| tx.events.end.0);
+        anyhow::ensure!(
+            next_item_numbers.batch_number == expected_batch_number
+                && next_item_numbers.tx_number == expected_tx_number
+                && next_item_numbers.event_number == expected_event_number,
+            "Ledger DB is inconsistent: expected next items {:?}, but the tables end at {:?}",
+            ItemNumbers {
+                slot_number: next_item_numbers.slot_number,
+                batch_number: expected_batch_number,
+                tx_number: expected_tx_number,
+                event_number: expected_event_number,
+            },
+            next_item_numbers
+        );
+
+        // The secondary indexes must contain the last items.
+        if let Some((number, slot)) = &last_slot {
+            anyhow::ensure!(
+                db.get::<SlotByHash>(&slot.hash)?.as_ref() == Some(number),
+                "Ledger DB is inconsistent: slot {} is missing from the slot hash index",
+                number.0
+            );
+        }
+        if let Some((number, batch)) = &last_batch {
+            anyhow::ensure!(
+                db.get::<BatchByHash>(&batch.hash)?.as_ref() == Some(number),
+                "Ledger DB is inconsistent: batch {} is missing from the batch hash index",
+                number.0
+            );
+        }
+        if let Some((number, tx)) = &last_tx {
+            anyhow::ensure!(
+                db.get::<TxByHash>(&tx.hash)?.as_ref() == Some(number),
+                "Ledger DB is inconsistent: tx {} is missing from the tx hash index",
+                number.0
+            );
+            if let Some((event_number, event)) = &last_event {
+                if tx.events.contains(event_number) {
+                    anyhow::ensure!(
+                        db.get::<EventByKey>(&(event.key().clone(), *number, *event_number))?
+                            .is_some(),
+                        "Ledger DB is inconsistent: event {} is missing from the event key index",
+                        event_number.0
+                    );
+                }
+            }
+        }
+
+        Ok(next_item_numbers)
+    }
+
+    fn last_entry<T, K, V>(db: &DB) -> anyhow::Result<Option<(K, V)>>
+    where
+        T: Schema<Key = K, Value = V>,
+    {
         let mut iter = db.iter::<T>()?;
         iter.seek_to_last();
+        iter.next().transpose()
+    }
+}
+
+#[cfg(test)]
+mod ledger_db_tests {
+    use sov_rollup_interface::mocks::TestBlock;
+    use sov_rollup_interface::rpc::{
+        EventCursor, EventIdentifier, EventsByKeyQuery, LedgerRpcProvider, QueryMode,
+        StateRootResponse, TxIdAndKey, TxIdentifier,
+    };
+    use sov_rollup_interface::services::da::SlotData;
+    use sov_rollup_interface::stf::{BatchReceipt, Event, TransactionReceipt};
+    use sov_schema_db::temppath::TempPath;
+    use sov_schema_db::SchemaBatch;
+
+    use super::{
+        ItemNumbers, LedgerDB, NodeMode, PrunedError, SlotCommit, SlotNotification,
+        LEDGER_FORMAT_VERSION,
+    };
+    use crate::schema::tables::{BatchByNumber, LedgerFormatVersion, TxByNumber};
+    use crate::schema::types::{BatchNumber, StoredBatch, TxNumber};
+
+    fn slot_commit(slot_byte: u8, num_txs: u8) -> SlotCommit<TestBlock, u64, u64> {
+        let mut slot_commit = SlotCommit::new(TestBlock::new([slot_byte - 1; 32], [slot_byte; 32]));
+        let tx_receipts = (0..num_txs)
+            .map(|i| TransactionReceipt {
+                tx_hash: [slot_byte.wrapping_mul(16).wrapping_add(i); 32],
+                body_to_save: None,
+                events: vec![Event::new("key", "value")],
+                receipt: 0,
+            })
+            .collect();
+        slot_commit.add_batch(BatchReceipt {
+            batch_hash: [slot_byte; 32],
+            tx_receipts,
+            inner: 0,
+        });
+        slot_commit
+    }
+
+    #[test]
+    fn test_item_numbers_recovered_on_restart() {
+        let path = TempPath::new();
+        {
+            let ledger_db = LedgerDB::with_path(&path).unwrap();
+            ledger_db.commit_slot(slot_commit(1, 2)).unwrap();
+            ledger_db.commit_slot(slot_commit(2, 3)).unwrap();
+        }
+
+        let ledger_db = LedgerDB::with_path(&path).unwrap();
+        let item_numbers = ledger_db.get_next_items_numbers();
+        assert_eq!(item_numbers.slot_number, 3);
+        assert_eq!(item_numbers.batch_number, 3);
+        assert_eq!(item_numbers.tx_number, 6);
+        assert_eq!(item_numbers.event_number, 6);
+    }
+
+    #[test]
+    fn test_format_version_checked_on_restart() {
+        let path = TempPath::new();
+        {
+            let ledger_db = LedgerDB::with_path(&path).unwrap();
+            ledger_db.commit_slot(slot_commit(1, 2)).unwrap();
+            assert_eq!(
+                ledger_db.db.get::<LedgerFormatVersion>(&()).unwrap(),
+                Some(LEDGER_FORMAT_VERSION)
+            );
+        }
+
+        // A ledger written in another format is refused
+        {
+            let ledger_db = LedgerDB::with_path(&path).unwrap();
+            ledger_db
+                .db
+                .put::<LedgerFormatVersion>(&(), &(LEDGER_FORMAT_VERSION + 1))
+                .unwrap();
+        }
+        assert!(LedgerDB::with_path(&path).is_err());
+
+        // So is a non-empty ledger written before the format was versioned
+        let unversioned_path = TempPath::new();
+        {
+            let ledger_db = LedgerDB::with_path(&unversioned_path).unwrap();
+            ledger_db.commit_slot(slot_commit(1, 2)).unwrap();
+            let batch = SchemaBatch::new();
+            batch.delete::<LedgerFormatVersion>(&()).unwrap();
+            ledger_db.db.write_schemas(batch).unwrap();
+        }
+        assert!(LedgerDB::with_path(&unversioned_path).is_err());
+    }
+
+    #[test]
+    fn test_last_state_version() {
+        let ledger_db = LedgerDB::temporary();
+        assert_eq!(ledger_db.get_last_state_version().unwrap(), None);
+
+        let mut first_slot = slot_commit(1, 1);
+        first_slot.set_state_version(3);
+        ledger_db.commit_slot(first_slot).unwrap();
+        assert_eq!(ledger_db.get_last_state_version().unwrap(), Some(3));
+
+        let mut second_slot = slot_commit(2, 1);
+        second_slot.set_state_version(4);
+        ledger_db.commit_slot(second_slot).unwrap();
+        assert_eq!(ledger_db.get_last_state_version().unwrap(), Some(4));
+    }
+
+    #[test]
+    fn test_rewind_to_slot() {
+        let path = TempPath::new();
+        {
+            let ledger_db = LedgerDB::with_path(&path).unwrap();
+            let mut first_slot = slot_commit(1, 2);
+            first_slot.set_state_version(2);
+            ledger_db.commit_slot(first_slot).unwrap();
+            let mut second_slot = slot_commit(2, 3);
+            second_slot.set_state_version(3);
+            ledger_db.commit_slot(second_slot).unwrap();
+            ledger_db.commit_slot(slot_commit(3, 1)).unwrap();
+
+            assert_eq!(ledger_db.rewind_to_slot(1).unwrap(), Some(2));
+            let item_numbers = ledger_db.get_next_items_numbers();
+            assert_eq!(item_numbers.slot_number, 2);
+            assert_eq!(item_numbers.batch_number, 2);
+            assert_eq!(item_numbers.tx_number, 3);
+            assert_eq!(item_numbers.event_number, 3);
+            assert_eq!(ledger_db.get_slot_hash(1).unwrap(), Some([1; 32]));
+            assert_eq!(ledger_db.get_slot_hash(2).unwrap(), None);
+            assert_eq!(ledger_db.get_last_state_version().unwrap(), Some(2));
+
+            // The slot on the new fork replaces the rewound one.
+            ledger_db.commit_slot(slot_commit(5, 1)).unwrap();
+            assert_eq!(ledger_db.get_slot_hash(2).unwrap(), Some([5; 32]));
+        }
+
+        // The rewound ledger is consistent after a restart.
+        let ledger_db = LedgerDB::with_path(&path).unwrap();
+        let item_numbers = ledger_db.get_next_items_numbers();
+        assert_eq!(item_numbers.slot_number, 3);
+        assert_eq!(item_numbers.tx_number, 4);
+
+        assert_eq!(ledger_db.rewind_to_slot(0).unwrap(), None);
+        assert_eq!(ledger_db.get_next_items_numbers().slot_number, 1);
+        assert_eq!(ledger_db.get_slot_hash(1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_events_by_key() {
+        let ledger_db = LedgerDB::temporary();
+        ledger_db.commit_slot(slot_commit(1, 2)).unwrap();
+        ledger_db.commit_slot(slot_commit(2, 3)).unwrap();
+
+        let key = Event::new("key", "value").key().clone();
+        let query_page = |tx_range, cursor| {
+            let query = EventsByKeyQuery {
+                key: key.clone(),
+                tx_range,
+                cursor,
+                limit: Some(2),
+            };
+            let page = ledger_db.get_events_by_key(&query).unwrap();
+            let numbers: Vec<_> = page.events.iter().map(|event| event.number).collect();
+            (numbers, page.next_cursor)
+        };
+
+        let (numbers, cursor) = query_page(None, None);
+        assert_eq!(numbers, vec![1, 2]);
+        let (numbers, cursor) = query_page(None, cursor);
+        assert_eq!(numbers, vec![3, 4]);
+        let (numbers, cursor) = query_page(None, cursor);
+        assert_eq!(numbers, vec![5]);
+        assert_eq!(cursor, None);
+
+        let (numbers, cursor) = query_page(Some(2..4), None);
+        assert_eq!(numbers, vec![2, 3]);
+        assert_eq!(cursor, None);
+
+        // A cursor before the start of the range doesn't return events outside of it
+        let cursor = Some(EventCursor {
+            tx_number: 1,
+            event_number: 1,
+        });
+        let (numbers, cursor) = query_page(Some(2..4), cursor);
+        assert_eq!(numbers, vec![2, 3]);
+        assert_eq!(cursor, None);
 
-        return match iter.next() {
-            Some(Ok((version, _))) => Ok(Some(version.into())),
-            Some(Err(e)) => Err(e),
-            _ => Ok(None),
+        let other_key = EventsByKeyQuery {
+            key: Event::new("other_key", "value").key().clone(),
+            tx_range: None,
+            cursor: None,
+            limit: None,
         };
+        assert!(ledger_db
+            .get_events_by_key(&other_key)
+            .unwrap()
+            .events
+            .is_empty());
+
+        let event_id = EventIdentifier::TxIdAndKey(TxIdAndKey {
+            batch_id: TxIdentifier::Number(4),
+            key,
+        });
+        assert_eq!(
+            ledger_db.get_events(&[event_id]).unwrap(),
+            vec![ledger_db.get_event_by_number(4).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_subscribe_slots() {
+        let ledger_db = LedgerDB::temporary();
+        ledger_db.commit_slot(slot_commit(1, 2)).unwrap();
+
+        let mut subscription = ledger_db.subscribe_slots();
+        ledger_db.commit_slot(slot_commit(2, 3)).unwrap();
+        assert_eq!(
+            subscription.try_recv().unwrap(),
+            SlotNotification {
+                slot_number: 2,
+                tx_range: 3..6,
+            }
+        );
+        assert!(subscription.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_slot_extra_data() {
+        let ledger_db = LedgerDB::temporary();
+        let slot = slot_commit(1, 1);
+        let extra_data = slot.slot_data.extra_data_for_storage();
+        assert!(!extra_data.is_empty());
+        ledger_db.commit_slot(slot).unwrap();
+
+        let response = ledger_db
+            .get_slot_by_number::<u64, u64>(1, QueryMode::Compact)
+            .unwrap()
+            .unwrap();
+        assert_eq!(response.extra_data, extra_data);
+    }
+
+    #[test]
+    fn test_state_roots() {
+        let ledger_db = LedgerDB::temporary();
+        let mut first_slot = slot_commit(1, 1);
+        first_slot.set_state_root([7; 32], [8; 32]);
+        ledger_db.commit_slot(first_slot).unwrap();
+        ledger_db.commit_slot(slot_commit(2, 1)).unwrap();
+
+        assert_eq!(
+            ledger_db.get_state_root(1).unwrap(),
+            Some(StateRootResponse {
+                state_root: vec![7; 32],
+                witness_hash: [8; 32],
+            })
+        );
+        // The second slot was committed without a state root, and the third one doesn't exist.
+        assert_eq!(ledger_db.get_state_root(2).unwrap(), None);
+        assert_eq!(ledger_db.get_state_root(3).unwrap(), None);
+
+        ledger_db.rewind_to_slot(0).unwrap();
+        assert_eq!(ledger_db.get_state_root(1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_pruned_mode() {
+        let mut ledger_db = LedgerDB::temporary();
+        for slot_byte in 1..=5 {
+            ledger_db.commit_slot(slot_commit(slot_byte, 1)).unwrap();
+        }
+        // Switching the mode doesn't start the pruner, so the ledger is only pruned below.
+        ledger_db.mode = NodeMode::Pruned { keep_slots: 2 };
+
+        // Nothing is reported as pruned before it is deleted.
+        assert_eq!(
+            ledger_db.get_first_available_items().unwrap(),
+            ItemNumbers {
+                slot_number: 1,
+                batch_number: 1,
+                tx_number: 1,
+                event_number: 1,
+            }
+        );
+
+        let end = {
+            let next_item_numbers = ledger_db.next_item_numbers.lock().unwrap();
+            LedgerDB::prune(&ledger_db.db, &next_item_numbers, 2).unwrap()
+        };
+        let first_available = ledger_db.get_first_available_items().unwrap();
+        assert_eq!(
+            first_available,
+            ItemNumbers {
+                slot_number: 4,
+                batch_number: 4,
+                tx_number: 4,
+                event_number: 4,
+            }
+        );
+        assert_eq!(end, first_available);
+        assert!(ledger_db
+            .db
+            .get::<TxByNumber>(&TxNumber(3))
+            .unwrap()
+            .is_none());
+        assert!(ledger_db
+            .db
+            .get::<TxByNumber>(&TxNumber(4))
+            .unwrap()
+            .is_some());
+
+        let err = ledger_db
+            .get_tx_by_number::<u64>(3, QueryMode::Compact)
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<PrunedError>().unwrap(),
+            PrunedError {
+                item: "transaction",
+                number: 3,
+                first_available: 4,
+            }
+        );
+        assert!(ledger_db
+            .get_tx_by_number::<u64>(4, QueryMode::Compact)
+            .unwrap()
+            .is_some());
+        assert!(ledger_db.get_event_by_number(1).is_err());
+        // The slots themselves are kept, but not their batches.
+        assert!(ledger_db
+            .get_slot_by_number::<u64, u64>(1, QueryMode::Compact)
+            .unwrap()
+            .is_some());
+        assert!(ledger_db
+            .get_slot_by_number::<u64, u64>(1, QueryMode::Standard)
+            .is_err());
+
+        // Queries by key only return the events which were kept.
+        let query = EventsByKeyQuery {
+            key: Event::new("key", "value").key().clone(),
+            tx_range: None,
+            cursor: None,
+            limit: None,
+        };
+        let numbers: Vec<_> = ledger_db
+            .get_events_by_key(&query)
+            .unwrap()
+            .events
+            .iter()
+            .map(|event| event.number)
+            .collect();
+        assert_eq!(numbers, vec![4, 5]);
+
+        // The ledger can't be rewound to a pruned slot.
+        assert!(ledger_db.rewind_to_slot(2).is_err());
+        ledger_db.rewind_to_slot(4).unwrap();
+        assert_eq!(
+            ledger_db.get_first_available_items().unwrap(),
+            first_available
+        );
+    }
+
+    #[test]
+    fn test_archive_mode() {
+        let ledger_db = LedgerDB::temporary();
+        for slot_byte in 1..=5 {
+            ledger_db.commit_slot(slot_commit(slot_byte, 1)).unwrap();
+        }
+        assert_eq!(
+            ledger_db.get_first_available_items().unwrap(),
+            ItemNumbers::default()
+        );
+        assert!(ledger_db
+            .get_tx_by_number::<u64>(1, QueryMode::Compact)
+            .unwrap()
+            .is_some());
+        assert!(
+            LedgerDB::with_path_and_mode(TempPath::new(), NodeMode::Pruned { keep_slots: 0 })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_secondary() {
+        let primary_path = TempPath::new();
+        let secondary_path = TempPath::new();
+        let primary = LedgerDB::with_path(&primary_path).unwrap();
+        primary.commit_slot(slot_commit(1, 2)).unwrap();
+
+        let secondary = LedgerDB::secondary_with_path(
+            &primary_path,
+            &secondary_path,
+            NodeMode::Archive,
+            &Default::default(),
+        )
+        .unwrap();
+        assert_eq!(secondary.get_next_items_numbers().slot_number, 2);
+        let mut subscription = secondary.subscribe_slots();
+
+        primary.commit_slot(slot_commit(2, 3)).unwrap();
+        primary.commit_slot(slot_commit(3, 1)).unwrap();
+        // The slots committed by the primary are only visible once the secondary catches up.
+        assert_eq!(secondary.get_slot_hash(2).unwrap(), None);
+        secondary.catch_up_with_primary().unwrap();
+        assert_eq!(
+            secondary.get_next_items_numbers(),
+            primary.get_next_items_numbers()
+        );
+        assert_eq!(secondary.get_slot_hash(3).unwrap(), Some([3; 32]));
+        assert_eq!(
+            subscription.try_recv().unwrap(),
+            SlotNotification {
+                slot_number: 2,
+                tx_range: 3..6,
+            }
+        );
+        assert_eq!(
+            subscription.try_recv().unwrap(),
+            SlotNotification {
+                slot_number: 3,
+                tx_range: 6..7,
+            }
+        );
+
+        assert!(secondary.commit_slot(slot_commit(4, 1)).is_err());
+    }
+
+    #[test]
+    fn test_inconsistent_ledger_is_rejected() {
+        let path = TempPath::new();
+        {
+            let ledger_db = LedgerDB::with_path(&path).unwrap();
+            ledger_db.commit_slot(slot_commit(1, 1)).unwrap();
+
+            // A batch which isn't referenced by any slot, as left by a torn write.
+            let batch = StoredBatch {
+                hash: [9; 32],
+                txs: TxNumber(2)..TxNumber(2),
+                custom_receipt: vec![].into(),
+            };
+            ledger_db
+                .db
+                .put::<BatchByNumber>(&BatchNumber(2), &batch)
+                .unwrap();
+        }
+
+        assert!(LedgerDB::with_path(&path).is_err());
     }
 }
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use sov_schema_db::{SchemaBatch, DB};
+use tokio::sync::broadcast::error::RecvError;
+
+use super::{ItemNumbers, LedgerDB};
+use crate::schema::{
+    tables::{BatchByNumber, EventByNumber, LastPrunedSlot, TxByNumber},
+    types::{BatchNumber, EventNumber, SlotNumber, TxNumber},
+};
+
+/// The ledger is pruned every time the window of kept slots moves by this many slots, so that
+/// each pruning deletes and compacts a large enough range.
+const PRUNING_INTERVAL_SLOTS: u64 = 64;
+
+/// How much of the ledger history the node keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeMode {
+    /// Keep the whole history.
+    #[default]
+    Archive,
+    /// Keep all the slots, but only the batches, transactions and events of the last `keep_slots` slots.
+    /// The older data is deleted in the background, and the RPC methods fail with a [`PrunedError`] when it is requested.
+    Pruned { keep_slots: u64 },
+}
+
+/// The error returned when the requested data is outside of the window kept by a pruned node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrunedError {
+    /// The kind of item which was requested: "slot", "batch", "transaction" or "event".
+    pub item: &'static str,
+    pub number: u64,
+    /// The number of the first item of this kind which is still available.
+    pub first_available: u64,
+}
+
+impl std::fmt::Display for PrunedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} was pruned, the first available {} is {}",
+            self.item, self.number, self.item, self.first_available
+        )
+    }
+}
+
+impl std::error::Error for PrunedError {}
+
+impl LedgerDB {
+    /// Returns the numbers of the first slot, batch, transaction and event whose data is kept by the node.
+    /// Archive nodes keep everything, so all the numbers are `0`. Pruned nodes report the items after the
+    /// last slot the pruner actually deleted, which may be behind the window of kept slots.
+    pub fn get_first_available_items(&self) -> anyhow::Result<ItemNumbers> {
+        match self.mode {
+            NodeMode::Archive => Ok(ItemNumbers::default()),
+            NodeMode::Pruned { .. } => {
+                // The lock is held so that the boundary matches the items the pruner deleted.
+                let next_item_numbers = self.next_item_numbers.lock().unwrap();
+                let last_pruned_slot = self
+                    .db
+                    .get::<LastPrunedSlot>(&())?
+                    .map_or(0, |slot_number| slot_number.0);
+                Self::first_items_after_slot(&self.db, last_pruned_slot, &next_item_numbers)
+            }
+        }
+    }
+
+    /// Fails with a [`PrunedError`] if the item `number` is before the first available one.
+    pub(crate) fn ensure_not_pruned(
+        item: &'static str,
+        number: u64,
+        first_available: u64,
+    ) -> anyhow::Result<()> {
+        if number < first_available {
+            return Err(PrunedError {
+                item,
+                number,
+                first_available,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Starts the thread which prunes the ledger of a node in [`NodeMode::Pruned`]. The thread prunes the ledger
+    /// on startup if it was never pruned, then every time the window of kept slots moves by [`PRUNING_INTERVAL_SLOTS`]
+    /// slots past the last pruned slot, and stops once the `LedgerDB` is dropped. Pruning holds the lock on the item
+    /// numbers, like commits and rewinds, so that it never deletes items based on stale numbers.
+    pub(super) fn spawn_pruner(&self, keep_slots: u64) -> anyhow::Result<()> {
+        let db = self.db.clone();
+        let next_item_numbers = self.next_item_numbers.clone();
+        let mut slots = self.subscribe_slots();
+        std::thread::Builder::new()
+            .name("ledger-pruner".to_string())
+            .spawn(move || loop {
+                let next = next_item_numbers.lock().unwrap();
+                let pruned = Self::prune_if_due(&db, &next, keep_slots);
+                drop(next);
+                match pruned {
+                    Ok(Some(end)) => {
+                        if let Err(e) = Self::compact(&db, &end) {
+                            tracing::error!("Failed to compact the pruned ledger: {}", e);
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::error!("Failed to prune the ledger: {}", e),
+                }
+                match slots.blocking_recv() {
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return,
+                }
+            })?;
+        Ok(())
+    }
+
+    /// Prunes the ledger if the window of kept slots moved by [`PRUNING_INTERVAL_SLOTS`] slots since the last pruning,
+    /// and returns the numbers of the first items which were kept.
+    fn prune_if_due(
+        db: &DB,
+        next_item_numbers: &ItemNumbers,
+        keep_slots: u64,
+    ) -> anyhow::Result<Option<ItemNumbers>> {
+        let first_slot = next_item_numbers.slot_number.saturating_sub(keep_slots);
+        let due = db.get::<LastPrunedSlot>(&())?.map_or(true, |last_pruned| {
+            first_slot > last_pruned.0 + PRUNING_INTERVAL_SLOTS
+        });
+        if !due {
+            return Ok(None);
+        }
+        Self::prune(db, next_item_numbers, keep_slots).map(Some)
+    }
+
+    /// Returns the numbers of the first items of the last `keep_slots` slots.
+    fn first_items_in_window(
+        db: &DB,
+        next_item_numbers: &ItemNumbers,
+        keep_slots: u64,
+    ) -> anyhow::Result<ItemNumbers> {
+        let last_pruned_slot = next_item_numbers.slot_number.saturating_sub(keep_slots + 1);
+        Self::first_items_after_slot(db, last_pruned_slot, next_item_numbers)
+    }
+
+    /// Deletes the batches, transactions and events before the first items of the last `keep_slots` slots,
+    /// records the last pruned slot, and returns the numbers of the first items which were kept. The last batch,
+    /// transaction and event are never deleted, so that the next item numbers can still be recovered when the ledger
+    /// is opened. The caller must hold the lock on `next_item_numbers`.
+    pub(super) fn prune(
+        db: &DB,
+        next_item_numbers: &ItemNumbers,
+        keep_slots: u64,
+    ) -> anyhow::Result<ItemNumbers> {
+        let first = Self::first_items_in_window(db, next_item_numbers, keep_slots)?;
+        let end = ItemNumbers {
+            slot_number: first.slot_number,
+            batch_number: first
+                .batch_number
+                .min(next_item_numbers.batch_number.saturating_sub(1)),
+            tx_number: first
+                .tx_number
+                .min(next_item_numbers.tx_number.saturating_sub(1)),
+            event_number: first
+                .event_number
+                .min(next_item_numbers.event_number.saturating_sub(1)),
+        };
+
+        let schema_batch = SchemaBatch::new();
+        // The items deleted by earlier prunings were compacted away, so scanning from the start is cheap.
+        Self::delete_items(db, &ItemNumbers::default(), &end, &schema_batch)?;
+        schema_batch.put::<LastPrunedSlot>(&(), &SlotNumber(first.slot_number - 1))?;
+        db.write_schemas(schema_batch)?;
+        Ok(end)
+    }
+
+    /// Compacts the ranges of the tables deleted by [`LedgerDB::prune`]. Compaction doesn't change the data,
+    /// so it runs without holding the lock on the item numbers.
+    fn compact(db: &DB, end: &ItemNumbers) -> anyhow::Result<()> {
+        db.compact_range::<BatchByNumber>(&BatchNumber(0), &BatchNumber(end.batch_number))?;
+        db.compact_range::<TxByNumber>(&TxNumber(0), &TxNumber(end.tx_number))?;
+        db.compact_range::<EventByNumber>(&EventNumber(0), &EventNumber(end.event_number))?;
+        Ok(())
+    }
+}
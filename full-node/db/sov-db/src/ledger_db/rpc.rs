@@ -1,16 +1,18 @@
 use serde::de::DeserializeOwned;
 use sov_rollup_interface::{
     rpc::{
-        BatchIdAndOffset, BatchIdentifier, BatchResponse, EventIdentifier, ItemOrHash,
-        LedgerRpcProvider, QueryMode, SlotIdAndOffset, SlotIdentifier, SlotResponse, TxIdAndOffset,
-        TxIdentifier, TxResponse,
+        BatchIdAndOffset, BatchIdentifier, BatchResponse, EventCursor, EventIdentifier,
+        EventResponse, EventsByKeyQuery, EventsByKeyResponse, ItemOrHash, LedgerRpcProvider,
+        QueryMode, SlotIdAndOffset, SlotIdentifier, SlotResponse, StateRootResponse, TxIdAndKey,
+        TxIdAndOffset, TxIdentifier, TxResponse,
     },
-    stf::Event,
+    stf::{Event, EventKey},
 };
 
 use crate::schema::{
     tables::{
-        BatchByHash, BatchByNumber, EventByNumber, SlotByHash, SlotByNumber, TxByHash, TxByNumber,
+        BatchByHash, BatchByNumber, EventByKey, EventByNumber, SlotByHash, SlotByNumber, TxByHash,
+        TxByNumber,
     },
     types::{BatchNumber, EventNumber, SlotNumber, StoredBatch, StoredSlot, TxNumber},
 };
@@ -40,11 +42,16 @@ impl LedgerRpcProvider for LedgerDB {
         );
         // TODO: https://github.com/Sovereign-Labs/sovereign/issues/191 Sort the input
         //      and use an iterator instead of querying for each slot individually
+        let first_available = self.get_first_available_items()?;
         let mut out = Vec::with_capacity(slot_ids.len());
         for slot_id in slot_ids {
             let slot_num = self.resolve_slot_identifier(slot_id)?;
             out.push(match slot_num {
                 Some(num) => {
+                    // The slots themselves are never pruned, only the batches they contain
+                    if query_mode != QueryMode::Compact {
+                        Self::ensure_not_pruned("slot", num.0, first_available.slot_number)?;
+                    }
                     if let Some(stored_slot) = self.db.get::<SlotByNumber>(&num)? {
                         Some(self.populate_slot_response(num.into(), stored_slot, query_mode)?)
                     } else {
@@ -70,11 +77,13 @@ impl LedgerRpcProvider for LedgerDB {
         );
         // TODO: https://github.com/Sovereign-Labs/sovereign/issues/191 Sort the input
         //      and use an iterator instead of querying for each slot individually
+        let first_available = self.get_first_available_items()?;
         let mut out = Vec::with_capacity(batch_ids.len());
         for batch_id in batch_ids {
             let batch_num = self.resolve_batch_identifier(batch_id)?;
             out.push(match batch_num {
                 Some(num) => {
+                    Self::ensure_not_pruned("batch", num.0, first_available.batch_number)?;
                     if let Some(stored_batch) = self.db.get::<BatchByNumber>(&num)? {
                         Some(self.populate_batch_response(stored_batch, query_mode)?)
                     } else {
@@ -100,11 +109,13 @@ impl LedgerRpcProvider for LedgerDB {
         );
         // TODO: https://github.com/Sovereign-Labs/sovereign/issues/191 Sort the input
         //      and use an iterator instead of querying for each slot individually
+        let first_available = self.get_first_available_items()?;
         let mut out: Vec<Option<TxResponse<T>>> = Vec::with_capacity(tx_ids.len());
         for id in tx_ids {
             let num = self.resolve_tx_identifier(id)?;
             out.push(match num {
                 Some(num) => {
+                    Self::ensure_not_pruned("transaction", num.0, first_available.tx_number)?;
                     if let Some(tx) = self.db.get::<TxByNumber>(&num)? {
                         Some(tx.try_into()?)
                     } else {
@@ -129,17 +140,95 @@ impl LedgerRpcProvider for LedgerDB {
         );
         // TODO: Sort the input and use an iterator instead of querying for each slot individually
         // https://github.com/Sovereign-Labs/sovereign/issues/191
+        let first_available = self.get_first_available_items()?;
         let mut out = Vec::with_capacity(event_ids.len());
         for id in event_ids {
             let num = self.resolve_event_identifier(id)?;
             out.push(match num {
-                Some(num) => self.db.get::<EventByNumber>(&num)?,
+                Some(num) => {
+                    Self::ensure_not_pruned("event", num.0, first_available.event_number)?;
+                    self.db.get::<EventByNumber>(&num)?
+                }
                 None => None,
             })
         }
         Ok(out)
     }
 
+    fn get_events_by_key(
+        &self,
+        query: &EventsByKeyQuery,
+    ) -> Result<EventsByKeyResponse, anyhow::Error> {
+        let limit = query.limit.unwrap_or(MAX_EVENTS_PER_REQUEST);
+        anyhow::ensure!(
+            limit > 0 && limit <= MAX_EVENTS_PER_REQUEST,
+            "limit must be between 1 and {}. Requested: {}",
+            MAX_EVENTS_PER_REQUEST,
+            limit
+        );
+        let first_available = self.get_first_available_items()?;
+        // Queries without an explicit start only cover the transactions which are still available
+        let tx_range = query
+            .tx_range
+            .clone()
+            .unwrap_or(first_available.tx_number..u64::MAX);
+        // A cursor never moves the scan before the start of the requested range
+        let start = match query.cursor {
+            Some(cursor) if cursor.tx_number >= tx_range.start => cursor,
+            _ => EventCursor {
+                tx_number: tx_range.start,
+                event_number: 0,
+            },
+        };
+        Self::ensure_not_pruned("transaction", start.tx_number, first_available.tx_number)?;
+
+        let mut iter = self.db.iter::<EventByKey>()?;
+        iter.seek(&(
+            query.key.clone(),
+            TxNumber(start.tx_number),
+            EventNumber(start.event_number),
+        ))?;
+        let mut events = Vec::new();
+        for entry in iter {
+            let ((key, tx_number, event_number), ()) = entry?;
+            if key != query.key || tx_number.0 >= tx_range.end {
+                break;
+            }
+            if events.len() as u64 == limit {
+                return Ok(EventsByKeyResponse {
+                    events,
+                    next_cursor: Some(EventCursor {
+                        tx_number: tx_number.0,
+                        event_number: event_number.0,
+                    }),
+                });
+            }
+            let event = self
+                .db
+                .get::<EventByNumber>(&event_number)?
+                .ok_or_else(|| {
+                    anyhow::anyhow!("Event {} is missing from the ledger", event_number.0)
+                })?;
+            events.push(EventResponse {
+                number: event_number.0,
+                tx_number: tx_number.0,
+                event,
+            });
+        }
+        Ok(EventsByKeyResponse {
+            events,
+            next_cursor: None,
+        })
+    }
+
+    fn get_state_root(&self, slot_number: u64) -> Result<Option<StateRootResponse>, anyhow::Error> {
+        Ok(self
+            .db
+            .get::<SlotByNumber>(&SlotNumber(slot_number))?
+            .and_then(|slot| slot.state_root)
+            .map(Into::into))
+    }
+
     fn get_head<B: DeserializeOwned, T: DeserializeOwned>(
         &self,
     ) -> Result<Option<SlotResponse<B, T>>, anyhow::Error> {
@@ -342,7 +431,29 @@ impl LedgerDB {
                 }
             }
             EventIdentifier::Number(num) => Ok(Some(EventNumber(*num))),
-            EventIdentifier::TxIdAndKey(_) => todo!(),
+            EventIdentifier::TxIdAndKey(TxIdAndKey { batch_id, key }) => {
+                if let Some(tx_num) = self.resolve_tx_identifier(batch_id)? {
+                    self.first_event_with_key(key, tx_num)
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    /// Returns the number of the first event emitted by the transaction `tx_number` with the key `key`.
+    fn first_event_with_key(
+        &self,
+        key: &EventKey,
+        tx_number: TxNumber,
+    ) -> Result<Option<EventNumber>, anyhow::Error> {
+        let mut iter = self.db.iter::<EventByKey>()?;
+        iter.seek(&(key.clone(), tx_number, EventNumber(0)))?;
+        match iter.next().transpose()? {
+            Some(((found_key, found_tx_number, event_number), ())) => {
+                Ok((&found_key == key && found_tx_number == tx_number).then_some(event_number))
+            }
+            None => Ok(None),
         }
     }
 
@@ -358,6 +469,7 @@ impl LedgerDB {
                 hash: slot.hash,
                 batch_range: slot.batches.start.into()..slot.batches.end.into(),
                 batches: None,
+                extra_data: slot.extra_data.as_ref().to_vec(),
             },
             QueryMode::Standard => {
                 let batches = self.get_batch_range(&slot.batches)?;
@@ -372,6 +484,7 @@ impl LedgerDB {
                     hash: slot.hash,
                     batch_range: slot.batches.start.into()..slot.batches.end.into(),
                     batches: batch_hashes,
+                    extra_data: slot.extra_data.as_ref().to_vec(),
                 }
             }
             QueryMode::Full => {
@@ -386,6 +499,7 @@ impl LedgerDB {
                     hash: slot.hash,
                     batch_range: slot.batches.start.into()..slot.batches.end.into(),
                     batches: Some(batches),
+                    extra_data: slot.extra_data.as_ref().to_vec(),
                 }
             }
         })
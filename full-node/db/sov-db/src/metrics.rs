@@ -0,0 +1,18 @@
+use once_cell::sync::Lazy;
+use prometheus::{register_int_counter, IntCounter};
+
+pub static STATE_DB_VALUE_CACHE_HITS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "sov_state_db_value_cache_hits",
+        "Reads of the latest state served by the value cache of the state db"
+    )
+    .unwrap()
+});
+
+pub static STATE_DB_VALUE_CACHE_MISSES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "sov_state_db_value_cache_misses",
+        "Reads of the latest state missing from the value cache of the state db"
+    )
+    .unwrap()
+});
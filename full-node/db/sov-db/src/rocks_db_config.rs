@@ -1,13 +1,18 @@
 // Adapted from Aptos-Core.
-// Modified to remove serde dependency
 
-use rocksdb::Options;
+use rocksdb::{BlockBasedOptions, Cache, ColumnFamilyDescriptor, Options, SliceTransform};
+use serde::{Deserialize, Serialize};
+
+use crate::schema::tables::JmtValues;
 
 /// Port selected RocksDB options for tuning underlying rocksdb instance of our state db.
 /// The current default values are taken from Aptos. TODO: tune rocksdb for our workload.
 /// see <https://github.com/facebook/rocksdb/blob/master/include/rocksdb/options.h>
 /// for detailed explanations.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///
+/// Each db is configured separately. The fields missing from a deserialized config keep their default value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RocksdbConfig {
     pub max_open_files: i32,
     pub max_total_wal_size: u64,
@@ -15,6 +20,17 @@ pub struct RocksdbConfig {
     pub block_cache_size: u64,
     pub block_size: u64,
     pub cache_index_and_filter_blocks: bool,
+    /// The number of bits per key of the bloom filters of the sst files, `0` disables the filters.
+    pub bloom_filter_bits_per_key: u32,
+    /// Size the levels of the LSM tree dynamically, which bounds the space used by obsolete versions of the records.
+    pub level_compaction_dynamic_level_bytes: bool,
+    /// Build the bloom filters of the `JmtValues` table (state db only) on the state key, without the version.
+    /// Reading a value seeks to the last version of its key, so the filters let the seek skip the sst files which
+    /// don't contain the key at all.
+    pub jmt_values_prefix_bloom: bool,
+    /// The number of state values cached in memory by the state db, on top of the rocksdb block cache, `0` disables the cache.
+    /// The cache is shared across slots and only serves the reads of the latest version of the state.
+    pub value_cache_size: usize,
 }
 
 impl Default for RocksdbConfig {
@@ -34,6 +50,12 @@ impl Default for RocksdbConfig {
             block_size: 4 * (1u64 << 10),
             // Whether cache index and filter blocks into block cache.
             cache_index_and_filter_blocks: false,
+            // The usual 1% false positive rate.
+            bloom_filter_bits_per_key: 10,
+            // Changing this on an existing db only applies to the data compacted afterwards.
+            level_compaction_dynamic_level_bytes: false,
+            jmt_values_prefix_bloom: true,
+            value_cache_size: 16_384,
         }
     }
 }
@@ -50,3 +72,60 @@ pub fn gen_rocksdb_options(config: &RocksdbConfig, readonly: bool) -> Options {
 
     db_opts
 }
+
+/// Generates the options of a secondary instance, which has to keep all its files open to follow the primary.
+/// See <https://github.com/facebook/rocksdb/wiki/Read-only-and-Secondary-instances>.
+pub fn gen_secondary_rocksdb_options(config: &RocksdbConfig) -> Options {
+    let mut db_opts = gen_rocksdb_options(config, true);
+    db_opts.set_max_open_files(-1);
+    db_opts
+}
+
+/// Generates the descriptors of the column families `tables`, which share a single block cache.
+pub fn gen_cf_descriptors(
+    config: &RocksdbConfig,
+    tables: &[&'static str],
+) -> Result<Vec<ColumnFamilyDescriptor>, anyhow::Error> {
+    let cache = Cache::new_lru_cache(config.block_cache_size as usize)?;
+    Ok(tables
+        .iter()
+        .map(|&table| {
+            let mut block_opts = BlockBasedOptions::default();
+            block_opts.set_block_cache(&cache);
+            block_opts.set_block_size(config.block_size as usize);
+            block_opts.set_cache_index_and_filter_blocks(config.cache_index_and_filter_blocks);
+            if config.bloom_filter_bits_per_key > 0 {
+                block_opts.set_bloom_filter(config.bloom_filter_bits_per_key as f64, false);
+            }
+
+            let mut cf_opts = Options::default();
+            cf_opts.set_compression_type(rocksdb::DBCompressionType::Lz4);
+            cf_opts.set_level_compaction_dynamic_level_bytes(
+                config.level_compaction_dynamic_level_bytes,
+            );
+            if table == JmtValues::table_name() && config.jmt_values_prefix_bloom {
+                // The filters are only built on prefixes, so that the seeks for a key at any version can use them
+                block_opts.set_whole_key_filtering(false);
+                cf_opts.set_prefix_extractor(SliceTransform::create(
+                    "jmt_values_state_key",
+                    jmt_values_state_key,
+                    Some(has_jmt_version),
+                ));
+            }
+            cf_opts.set_block_based_table_factory(&block_opts);
+            ColumnFamilyDescriptor::new(table, cf_opts)
+        })
+        .collect())
+}
+
+/// The keys of the `JmtValues` table are the encoded state key followed by the version in 8 bytes,
+/// see `KeyEncoder<JmtValues>`.
+const JMT_VERSION_LEN: usize = std::mem::size_of::<jmt::Version>();
+
+fn jmt_values_state_key(key: &[u8]) -> &[u8] {
+    &key[..key.len() - JMT_VERSION_LEN]
+}
+
+fn has_jmt_version(key: &[u8]) -> bool {
+    key.len() >= JMT_VERSION_LEN
+}
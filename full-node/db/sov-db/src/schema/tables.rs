@@ -15,6 +15,12 @@
 //! Event Tables:
 //! - (EventKey, TxNumber) -> EventNumber
 //! - EventNumber -> (EventKey, EventValue)
+//!
+//! State Tables:
+//! - KeyHash -> StateKey
+//! - (StateKey, Version) -> JmtValue
+//! - NodeKey -> Node
+//! - () -> Version
 
 use super::types::{
     BatchNumber, DbHash, EventNumber, JmtValue, SlotNumber, StateKey, StoredBatch, StoredSlot,
@@ -37,6 +43,7 @@ pub const STATE_TABLES: &[&str] = &[
     KeyHashToKey::table_name(),
     JmtValues::table_name(),
     JmtNodes::table_name(),
+    LatestStateVersion::table_name(),
 ];
 
 pub const LEDGER_TABLES: &[&str] = &[
@@ -48,6 +55,8 @@ pub const LEDGER_TABLES: &[&str] = &[
     TxByNumber::table_name(),
     EventByKey::table_name(),
     EventByNumber::table_name(),
+    LedgerFormatVersion::table_name(),
+    LastPrunedSlot::table_name(),
 ];
 
 /// Macro to define a table that implements [`sov_rollup_interface::db::Schema`].
@@ -136,8 +145,52 @@ macro_rules! define_table_with_default_codec {
     };
 }
 
+/// Macro to define a table keyed by a number (see `u64_wrapper!`) that implements [`sov_rollup_interface::db::Schema`].
+/// Keys are encoded in big-endian order, so that iterating over the table visits the keys in numeric order.
+/// Values are encoded with borsh.
+///
+/// ```ignore
+/// define_table_with_seek_key_codec!(
+///  /// A table storing keys and value
+///  (MyTable) MyNumber => MyValue
+/// )
+/// ```
+macro_rules! define_table_with_seek_key_codec {
+    ($(#[$docs:meta])+ ($table_name:ident) $key:ty => $value:ty) => {
+        define_table_without_codec!($(#[$docs])+ ( $table_name ) $key => $value);
+
+        impl ::sov_rollup_interface::db::KeyEncoder<$table_name> for $key {
+            fn encode_key(&self) -> ::std::result::Result<::sov_rollup_interface::maybestd::vec::Vec<u8>, ::sov_rollup_interface::db::errors::CodecError> {
+                Ok(self.0.to_be_bytes().to_vec())
+            }
+        }
+
+        impl ::sov_rollup_interface::db::KeyDecoder<$table_name> for $key {
+            fn decode_key(data: &[u8]) -> ::std::result::Result<Self, ::sov_rollup_interface::db::errors::CodecError> {
+                let bytes: [u8; 8] = data.try_into().map_err(|_| {
+                    ::sov_rollup_interface::db::errors::CodecError::InvalidKeyLength {
+                        expected: 8,
+                        got: data.len(),
+                    }
+                })?;
+                Ok(Self(u64::from_be_bytes(bytes)))
+            }
+        }
+
+        impl ::sov_rollup_interface::db::ValueCodec<$table_name> for $value {
+            fn encode_value(&self) -> ::std::result::Result<::sov_rollup_interface::maybestd::vec::Vec<u8>, ::sov_rollup_interface::db::errors::CodecError> {
+                <Self as ::borsh::BorshSerialize>::try_to_vec(self).map_err(|e| e.into())
+            }
+
+            fn decode_value(data: &[u8]) -> ::std::result::Result<Self, ::sov_rollup_interface::db::errors::CodecError> {
+                <Self as ::borsh::BorshDeserialize>::deserialize_reader(&mut &data[..]).map_err(|e| e.into())
+            }
+        }
+    };
+}
+
 // fn deser(target: &mut &[u8]) -> Result<Self, DeserializationError>;
-define_table_with_default_codec!(
+define_table_with_seek_key_codec!(
     /// The primary source for slot data
     (SlotByNumber) SlotNumber => StoredSlot
 );
@@ -147,7 +200,7 @@ define_table_with_default_codec!(
     (SlotByHash) DbHash => SlotNumber
 );
 
-define_table_with_default_codec!(
+define_table_with_seek_key_codec!(
     /// The primary source for batch data
     (BatchByNumber) BatchNumber => StoredBatch
 );
@@ -157,7 +210,7 @@ define_table_with_default_codec!(
     (BatchByHash) DbHash => BatchNumber
 );
 
-define_table_with_default_codec!(
+define_table_with_seek_key_codec!(
     /// The primary source for transaction data
     (TxByNumber) TxNumber => StoredTransaction
 );
@@ -167,16 +220,59 @@ define_table_with_default_codec!(
     (TxByHash) DbHash => TxNumber
 );
 
-define_table_with_default_codec!(
+define_table_with_seek_key_codec!(
     /// The primary store for event data
     (EventByNumber) EventNumber => Event
 );
 
-define_table_with_default_codec!(
+define_table_without_codec!(
     /// A "secondary index" for event data by key
     (EventByKey) (EventKey, TxNumber, EventNumber) => ()
 );
 
+define_table_with_default_codec!(
+    /// The version of the on-disk format of the ledger. The table holds a single entry, keyed by `()`.
+    (LedgerFormatVersion) () => u64
+);
+
+define_table_with_default_codec!(
+    /// The last slot whose batches, transactions and events were deleted by the pruner. The table holds a single
+    /// entry, keyed by `()`, and is only written by pruned nodes.
+    (LastPrunedSlot) () => SlotNumber
+);
+
+impl KeyEncoder<EventByKey> for (EventKey, TxNumber, EventNumber) {
+    fn encode_key(&self) -> sov_rollup_interface::db::Result<Vec<u8>> {
+        let mut out = self.0.try_to_vec().map_err(CodecError::from)?;
+        // Write the numbers in big-endian order so that the events with the same key are sorted by tx and event number
+        out.write_u64::<BigEndian>(self.1 .0)
+            .expect("serialization to vec is infallible");
+        out.write_u64::<BigEndian>(self.2 .0)
+            .expect("serialization to vec is infallible");
+        Ok(out)
+    }
+}
+
+impl KeyDecoder<EventByKey> for (EventKey, TxNumber, EventNumber) {
+    fn decode_key(data: &[u8]) -> sov_rollup_interface::db::Result<Self> {
+        let mut cursor = maybestd::io::Cursor::new(data);
+        let key = EventKey::deserialize_reader(&mut cursor)?;
+        let tx_number = cursor.read_u64::<BigEndian>()?;
+        let event_number = cursor.read_u64::<BigEndian>()?;
+        Ok((key, TxNumber(tx_number), EventNumber(event_number)))
+    }
+}
+
+impl ValueCodec<EventByKey> for () {
+    fn encode_value(&self) -> sov_rollup_interface::db::Result<Vec<u8>> {
+        Ok(vec![])
+    }
+
+    fn decode_value(_data: &[u8]) -> sov_rollup_interface::db::Result<Self> {
+        Ok(())
+    }
+}
+
 define_table_without_codec!(
     /// The source of truth for JMT nodes
     (JmtNodes) NodeKey => Node
@@ -249,3 +345,8 @@ define_table_with_default_codec!(
     /// which requires the ability to fetch values by hash.
     (KeyHashToKey) [u8;32] => StateKey
 );
+
+define_table_with_default_codec!(
+    /// The latest version of the state written to the db. The table holds a single entry, keyed by `()`.
+    (LatestStateVersion) () => Version
+);
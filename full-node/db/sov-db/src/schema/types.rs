@@ -3,7 +3,7 @@ use std::sync::Arc;
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sov_rollup_interface::{
-    rpc::{BatchResponse, TxIdentifier, TxResponse},
+    rpc::{BatchResponse, StateRootResponse, TxIdentifier, TxResponse},
     stf::{Event, EventKey, TransactionReceipt},
 };
 
@@ -49,14 +49,35 @@ pub type DbHash = [u8; 32];
 pub type JmtValue = Option<Vec<u8>>;
 pub(crate) type StateKey = Vec<u8>;
 
-/// The on-disk format of a slot. Specifies the batches contained in the slot
-/// and the hash of the da block. TODO(@preston-evans98): add any additional data
-/// required to reconstruct the da block proof
+/// The on-disk format of a slot. Specifies the batches contained in the slot,
+/// the hash of the da block and the data returned by `SlotData::extra_data_for_storage`,
+/// such as the da block header.
 #[derive(Debug, PartialEq, BorshDeserialize, BorshSerialize)]
 pub struct StoredSlot {
     pub hash: DbHash,
     pub extra_data: DbBytes,
     pub batches: std::ops::Range<BatchNumber>,
+    /// The version of the state db after the slot was executed, if it is tracked by the node.
+    pub state_version: Option<u64>,
+    /// The state root after the slot was executed, if it is tracked by the node.
+    pub state_root: Option<StoredStateRoot>,
+}
+
+/// The on-disk format of the state root after a slot, together with the hash of the witness
+/// produced while executing the slot.
+#[derive(Debug, Clone, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct StoredStateRoot {
+    pub root: DbBytes,
+    pub witness_hash: DbHash,
+}
+
+impl From<StoredStateRoot> for StateRootResponse {
+    fn from(value: StoredStateRoot) -> Self {
+        Self {
+            state_root: value.root.as_ref().to_vec(),
+            witness_hash: value.witness_hash,
+        }
+    }
 }
 /// The on-disk format for a batch. Stores the hash and identifies the range of transactions
 /// included in the batch
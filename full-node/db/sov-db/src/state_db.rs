@@ -1,4 +1,5 @@
 use std::{
+    num::NonZeroUsize,
     path::Path,
     sync::{Arc, Mutex},
 };
@@ -8,12 +9,17 @@ use jmt::{
     KeyHash, Version,
 };
 
-use sov_schema_db::DB;
+use lru::LruCache;
+use rocksdb::ReadOptions;
+use sov_schema_db::{SchemaBatch, DB};
 
 use crate::{
-    rocks_db_config::gen_rocksdb_options,
+    metrics::{STATE_DB_VALUE_CACHE_HITS, STATE_DB_VALUE_CACHE_MISSES},
+    rocks_db_config::{
+        gen_cf_descriptors, gen_rocksdb_options, gen_secondary_rocksdb_options, RocksdbConfig,
+    },
     schema::{
-        tables::{JmtNodes, JmtValues, KeyHashToKey, STATE_TABLES},
+        tables::{JmtNodes, JmtValues, KeyHashToKey, LatestStateVersion, STATE_TABLES},
         types::StateKey,
     },
 };
@@ -22,28 +28,74 @@ use crate::{
 pub struct StateDB {
     db: Arc<DB>,
     next_version: Arc<Mutex<Version>>,
+    value_cache: Arc<Mutex<ValueCache>>,
 }
 
 const STATE_DB_PATH_SUFFIX: &str = "state";
 
 impl StateDB {
     pub fn with_path(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        Self::with_config(path, &RocksdbConfig::default())
+    }
+
+    /// Opens the state db with the given rocksdb tuning.
+    pub fn with_config(
+        path: impl AsRef<Path>,
+        rocksdb_config: &RocksdbConfig,
+    ) -> Result<Self, anyhow::Error> {
         let path = path.as_ref().join(STATE_DB_PATH_SUFFIX);
-        let inner = DB::open(
+        let inner = DB::open_cf(
+            &gen_rocksdb_options(rocksdb_config, false),
             path,
             "state-db",
-            STATE_TABLES.iter().copied(),
-            &gen_rocksdb_options(&Default::default(), false),
+            gen_cf_descriptors(rocksdb_config, STATE_TABLES)?,
+        )?;
+
+        Self::with_db(inner, rocksdb_config)
+    }
+
+    /// Opens the state db at `primary_path` as a read-only secondary, which keeps its own files at `secondary_path`.
+    /// The secondary only sees the versions written by the primary once [`StateDB::catch_up_with_primary`] is called.
+    pub fn secondary_with_path(
+        primary_path: impl AsRef<Path>,
+        secondary_path: impl AsRef<Path>,
+        rocksdb_config: &RocksdbConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let inner = DB::open_cf_as_secondary(
+            &gen_secondary_rocksdb_options(rocksdb_config),
+            primary_path.as_ref().join(STATE_DB_PATH_SUFFIX),
+            secondary_path.as_ref().join(STATE_DB_PATH_SUFFIX),
+            "state-db",
+            STATE_TABLES.to_vec(),
         )?;
 
-        let next_version = Self::last_version_written(&inner)?.unwrap_or_default() + 1;
+        Self::with_db(inner, rocksdb_config)
+    }
+
+    fn with_db(inner: DB, rocksdb_config: &RocksdbConfig) -> anyhow::Result<Self> {
+        let latest_version = Self::last_version_written(&inner)?.unwrap_or_default();
 
         Ok(Self {
             db: Arc::new(inner),
-            next_version: Arc::new(Mutex::new(next_version)),
+            next_version: Arc::new(Mutex::new(latest_version + 1)),
+            value_cache: Arc::new(Mutex::new(ValueCache::new(
+                rocksdb_config.value_cache_size,
+                latest_version,
+            ))),
         })
     }
 
+    /// Catches a secondary db up with the versions written by the primary since it was opened or last caught up.
+    pub fn catch_up_with_primary(&self) -> anyhow::Result<()> {
+        let mut next_version = self.next_version.lock().unwrap();
+        self.db.try_catch_up_with_primary()?;
+        let latest_version = Self::last_version_written(&self.db)?.unwrap_or_default();
+        // The secondary doesn't know which keys the primary wrote
+        self.value_cache.lock().unwrap().clear(latest_version);
+        *next_version = latest_version + 1;
+        Ok(())
+    }
+
     /// A rocksdb instance which stores its data in a tempdir
     #[cfg(any(test, feature = "temp"))]
     pub fn temporary() -> Self {
@@ -55,10 +107,32 @@ impl StateDB {
         self.db.put::<KeyHashToKey>(&key_hash.0, key)
     }
 
+    /// Returns the value of `key` at `version`. The reads of the latest version of the state are cached.
     pub fn get_value_option_by_key(
         &self,
         version: Version,
         key: &StateKey,
+    ) -> anyhow::Result<Option<jmt::OwnedValue>> {
+        let generation = {
+            let mut value_cache = self.value_cache.lock().unwrap();
+            if let Some(value) = value_cache.get(version, key) {
+                return Ok(value);
+            }
+            value_cache.generation
+        };
+
+        let value = self.read_value_by_key(version, key)?;
+        self.value_cache
+            .lock()
+            .unwrap()
+            .insert(version, key, &value, generation);
+        Ok(value)
+    }
+
+    fn read_value_by_key(
+        &self,
+        version: Version,
+        key: &StateKey,
     ) -> anyhow::Result<Option<jmt::OwnedValue>> {
         let mut iter = self.db.iter::<JmtValues>()?;
         // find the latest instance of the key whose version <= target
@@ -86,7 +160,12 @@ impl StateDB {
         next_version: Version,
     ) -> anyhow::Result<()> {
         self.put_preimage(key_hash, &key)?;
-        self.db.put::<JmtValues>(&(key, next_version), &value)?;
+        self.db
+            .put::<JmtValues>(&(key.clone(), next_version), &value)?;
+        self.value_cache
+            .lock()
+            .unwrap()
+            .invalidate(next_version, std::iter::once(&key));
         Ok(())
     }
 
@@ -100,7 +179,52 @@ impl StateDB {
         *version
     }
 
+    /// Returns the latest version written to the db.
+    pub fn get_latest_version(&self) -> Option<Version> {
+        let next_version = self.next_version.lock().unwrap();
+        next_version.checked_sub(1)
+    }
+
+    /// Discards all the nodes and values written after `version`, so that the next version
+    /// to be written is `version + 1`. Does nothing if the db is not ahead of `version`.
+    ///
+    /// This walks over the whole state, so it is only meant to be used for recovery.
+    pub fn rollback_to(&self, version: Version) -> anyhow::Result<()> {
+        let mut next_version = self.next_version.lock().unwrap();
+        if *next_version <= version + 1 {
+            return Ok(());
+        }
+
+        let batch = SchemaBatch::new();
+        for entry in self.db.iter::<JmtNodes>()? {
+            let (node_key, _) = entry?;
+            if node_key.version() > version {
+                batch.delete::<JmtNodes>(&node_key)?;
+            }
+        }
+        // The `JmtValues` table may have a prefix extractor, whose iterators only cover a single key by default.
+        let mut read_opts = ReadOptions::default();
+        read_opts.set_total_order_seek(true);
+        for entry in self.db.iter_with_opts::<JmtValues>(read_opts)? {
+            let ((key, value_version), _) = entry?;
+            if value_version > version {
+                batch.delete::<JmtValues>(&(key, value_version))?;
+            }
+        }
+        batch.put::<LatestStateVersion>(&(), &version)?;
+        self.db.write_schemas(batch)?;
+
+        self.value_cache.lock().unwrap().clear(version);
+        *next_version = version + 1;
+        Ok(())
+    }
+
     fn last_version_written(db: &DB) -> anyhow::Result<Option<Version>> {
+        if let Some(version) = db.get::<LatestStateVersion>(&())? {
+            return Ok(Some(version));
+        }
+        // Databases written before the `LatestStateVersion` table existed don't have the key,
+        // so we fall back to the version of the last value written to the `JmtValues` table.
         let mut iter = db.iter::<JmtValues>()?;
         iter.seek_to_last();
 
@@ -140,9 +264,15 @@ impl TreeReader for StateDB {
 }
 
 impl TreeWriter for StateDB {
+    /// Writes the nodes and values of a tree update atomically, together with the latest version
+    /// of the tree, so that a crash can't leave a partially written version behind.
     fn write_node_batch(&self, node_batch: &jmt::storage::NodeBatch) -> anyhow::Result<()> {
+        let batch = SchemaBatch::new();
+        let mut latest_version = None;
+        let mut keys = Vec::new();
         for (node_key, node) in node_batch.nodes() {
-            self.db.put::<JmtNodes>(node_key, node)?;
+            batch.put::<JmtNodes>(node_key, node)?;
+            latest_version = latest_version.max(Some(node_key.version()));
         }
 
         for ((version, key_hash), value) in node_batch.values() {
@@ -152,12 +282,95 @@ impl TreeWriter for StateDB {
                     .ok_or(anyhow::format_err!(
                         "Could not find preimage for key hash {key_hash:?}"
                     ))?;
-            self.db.put::<JmtValues>(&(key_preimage, *version), value)?;
+            batch.put::<JmtValues>(&(key_preimage.clone(), *version), value)?;
+            latest_version = latest_version.max(Some(*version));
+            keys.push(key_preimage);
+        }
+
+        if let Some(latest_version) = latest_version {
+            batch.put::<LatestStateVersion>(&(), &latest_version)?;
+        }
+        self.db.write_schemas(batch)?;
+
+        // Invalidated once the values are written, so that no read started before the write can cache an outdated value
+        if let Some(latest_version) = latest_version {
+            self.value_cache
+                .lock()
+                .unwrap()
+                .invalidate(latest_version, keys.iter());
         }
         Ok(())
     }
 }
 
+/// A bounded LRU cache of the latest values of the state keys.
+///
+/// Only the reads at or after the latest version written to the db are served by the cache, since no newer value
+/// can exist for them. The writes invalidate the keys they touch, and bump the generation of the cache so that the
+/// values read before the write can't be inserted afterwards.
+struct ValueCache {
+    /// `None` if the cache is disabled.
+    entries: Option<LruCache<StateKey, Option<jmt::OwnedValue>>>,
+    latest_version: Version,
+    generation: u64,
+}
+
+impl ValueCache {
+    fn new(size: usize, latest_version: Version) -> Self {
+        Self {
+            entries: NonZeroUsize::new(size).map(LruCache::new),
+            latest_version,
+            generation: 0,
+        }
+    }
+
+    fn get(&mut self, version: Version, key: &StateKey) -> Option<Option<jmt::OwnedValue>> {
+        if version < self.latest_version {
+            return None;
+        }
+        let value = self.entries.as_mut()?.get(key).cloned();
+        match value {
+            Some(_) => STATE_DB_VALUE_CACHE_HITS.inc(),
+            None => STATE_DB_VALUE_CACHE_MISSES.inc(),
+        }
+        value
+    }
+
+    fn insert(
+        &mut self,
+        version: Version,
+        key: &StateKey,
+        value: &Option<jmt::OwnedValue>,
+        generation: u64,
+    ) {
+        if version < self.latest_version || generation != self.generation {
+            return;
+        }
+        if let Some(entries) = self.entries.as_mut() {
+            entries.put(key.clone(), value.clone());
+        }
+    }
+
+    fn invalidate<'a>(&mut self, version: Version, keys: impl Iterator<Item = &'a StateKey>) {
+        self.latest_version = self.latest_version.max(version);
+        self.generation += 1;
+        if let Some(entries) = self.entries.as_mut() {
+            for key in keys {
+                entries.pop(key);
+            }
+        }
+    }
+
+    /// Empties the cache, after the db was rolled back or written by another process.
+    fn clear(&mut self, latest_version: Version) {
+        self.latest_version = latest_version;
+        self.generation += 1;
+        if let Some(entries) = self.entries.as_mut() {
+            entries.clear();
+        }
+    }
+}
+
 #[cfg(test)]
 mod state_db_tests {
     use jmt::{
@@ -165,7 +378,11 @@ mod state_db_tests {
         KeyHash,
     };
 
+    use sov_schema_db::temppath::TempPath;
+
     use super::StateDB;
+    use crate::metrics::STATE_DB_VALUE_CACHE_HITS;
+    use crate::schema::tables::JmtValues;
 
     #[test]
     fn test_simple() {
@@ -185,4 +402,97 @@ mod state_db_tests {
         let found = db.get_value_option_by_key(0, &key).unwrap().unwrap();
         assert_eq!(found, value);
     }
+
+    #[test]
+    fn test_jmt_values_prefix_bloom() {
+        let db = StateDB::temporary();
+        let keys = [vec![1u8; 3], vec![1u8; 4], vec![2u8; 3]];
+        for version in 1..=3u64 {
+            for key in &keys {
+                db.db
+                    .put::<JmtValues>(&(key.clone(), version), &Some(vec![version as u8]))
+                    .unwrap();
+            }
+            db.inc_next_version();
+        }
+        // Write the values to an sst file, so that the reads go through its bloom filters.
+        db.db.flush_cf(JmtValues::table_name()).unwrap();
+
+        assert_eq!(
+            db.get_value_option_by_key(2, &keys[1]).unwrap(),
+            Some(vec![2])
+        );
+        assert_eq!(
+            db.get_value_option_by_key(5, &keys[2]).unwrap(),
+            Some(vec![3])
+        );
+        assert_eq!(db.get_value_option_by_key(0, &keys[0]).unwrap(), None);
+        assert_eq!(db.get_value_option_by_key(3, &vec![3u8; 3]).unwrap(), None);
+
+        // The rollback iterates over the values of all the keys.
+        db.rollback_to(1).unwrap();
+        for key in &keys {
+            assert_eq!(db.get_value_option_by_key(3, key).unwrap(), Some(vec![1]));
+        }
+    }
+
+    #[test]
+    fn test_value_cache() {
+        let db = StateDB::temporary();
+        let key_hash = KeyHash([1u8; 32]);
+        let key = vec![2u8; 100];
+        db.put_preimage(key_hash, &key).unwrap();
+
+        let write = |version, value: Option<Vec<u8>>| {
+            let mut batch = NodeBatch::default();
+            batch.extend(vec![], vec![((version, key_hash), value)]);
+            db.write_node_batch(&batch).unwrap();
+            db.inc_next_version();
+        };
+
+        write(1, Some(vec![1]));
+        assert_eq!(db.get_value_option_by_key(2, &key).unwrap(), Some(vec![1]));
+        let hits = STATE_DB_VALUE_CACHE_HITS.get();
+        assert_eq!(db.get_value_option_by_key(2, &key).unwrap(), Some(vec![1]));
+        assert!(STATE_DB_VALUE_CACHE_HITS.get() > hits);
+
+        // A write invalidates the cached value, and the older versions are still read from the db.
+        write(2, Some(vec![2]));
+        assert_eq!(db.get_value_option_by_key(1, &key).unwrap(), Some(vec![1]));
+        assert_eq!(db.get_value_option_by_key(2, &key).unwrap(), Some(vec![2]));
+        write(3, None);
+        assert_eq!(db.get_value_option_by_key(3, &key).unwrap(), None);
+        assert_eq!(db.get_value_option_by_key(2, &key).unwrap(), Some(vec![2]));
+
+        db.rollback_to(2).unwrap();
+        assert_eq!(db.get_value_option_by_key(3, &key).unwrap(), Some(vec![2]));
+    }
+
+    #[test]
+    fn test_secondary() {
+        let primary_path = TempPath::new();
+        let secondary_path = TempPath::new();
+        let primary = StateDB::with_path(&primary_path).unwrap();
+        let secondary =
+            StateDB::secondary_with_path(&primary_path, &secondary_path, &Default::default())
+                .unwrap();
+        assert_eq!(secondary.get_latest_version(), Some(0));
+
+        let key_hash = KeyHash([1u8; 32]);
+        let key = vec![2u8; 100];
+        primary.put_preimage(key_hash, &key).unwrap();
+        let mut batch = NodeBatch::default();
+        batch.extend(vec![], vec![((1, key_hash), Some(vec![8u8; 150]))]);
+        primary.write_node_batch(&batch).unwrap();
+
+        // The writes of the primary are only visible once the secondary catches up.
+        assert_eq!(secondary.get_value_option_by_key(1, &key).unwrap(), None);
+        secondary.catch_up_with_primary().unwrap();
+        assert_eq!(secondary.get_latest_version(), Some(1));
+        assert_eq!(
+            secondary.get_value_option_by_key(1, &key).unwrap(),
+            Some(vec![8u8; 150])
+        );
+        assert!(secondary.put_preimage(key_hash, &key).is_err());
+    }
 }
@@ -163,6 +163,12 @@ impl DB {
         Ok(Self::log_construct(name, inner))
     }
 
+    /// Catches a db opened with [`DB::open_cf_as_secondary`] up with the writes made by the primary instance
+    /// since it was opened or last caught up.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        Ok(self.inner.try_catch_up_with_primary()?)
+    }
+
     fn log_construct(name: &'static str, inner: rocksdb::DB) -> DB {
         info!(rocksdb_name = name, "Opened RocksDB.");
         DB { name, inner }
@@ -286,6 +292,22 @@ impl DB {
         Ok(self.inner.flush_cf(self.get_cf_handle(cf_name)?)?)
     }
 
+    /// Compacts the records of a schema with keys in `start..end`, reclaiming the space used by
+    /// the records which were deleted in that range.
+    pub fn compact_range<S: Schema>(
+        &self,
+        start: &impl KeyCodec<S>,
+        end: &impl KeyCodec<S>,
+    ) -> Result<()> {
+        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
+        self.inner.compact_range_cf(
+            cf_handle,
+            Some(start.encode_key()?),
+            Some(end.encode_key()?),
+        );
+        Ok(())
+    }
+
     /// Returns the current rocksdb property value for the provided column family name
     /// and property name
     pub fn get_property(&self, cf_name: &str, property_name: &str) -> Result<u64> {
@@ -1,10 +1,11 @@
 use super::{
     types::{Candidate, Voter},
-    Election,
+    Election, ADMIN,
 };
 use anyhow::{anyhow, bail, ensure, Result};
 
 use sov_modules_api::{CallResponse, Context};
+use sov_modules_macros::AccessControl;
 use sov_state::WorkingSet;
 
 /// Call actions supported byte the module.
@@ -13,12 +14,19 @@ use sov_state::WorkingSet;
     derive(serde::Serialize),
     derive(serde::Deserialize)
 )]
-#[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone)]
+#[derive(
+    borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone, AccessControl,
+)]
 pub enum CallMessage<C: Context> {
-    SetCandidates { names: Vec<String> },
+    #[access(role = ADMIN)]
+    SetCandidates {
+        names: Vec<String>,
+    },
+    #[access(role = ADMIN)]
     AddVoter(C::Address),
     Vote(usize),
     ClearElection,
+    #[access(role = ADMIN)]
     FreezeElection,
 }
 
@@ -31,7 +39,8 @@ impl<C: sov_modules_api::Context> Election<C> {
         working_set: &mut WorkingSet<C::Storage>,
     ) -> Result<CallResponse> {
         self.exit_if_frozen(working_set)?;
-        self.exit_if_not_admin(context, working_set)?;
+        self.roles
+            .ensure_role(ADMIN, context.sender(), working_set)?;
         self.exit_if_candidates_already_set(working_set)?;
 
         let candidates = candidate_names.into_iter().map(Candidate::new).collect();
@@ -49,7 +58,8 @@ impl<C: sov_modules_api::Context> Election<C> {
         working_set: &mut WorkingSet<C::Storage>,
     ) -> Result<CallResponse> {
         self.exit_if_frozen(working_set)?;
-        self.exit_if_not_admin(context, working_set)?;
+        self.roles
+            .ensure_role(ADMIN, context.sender(), working_set)?;
         self.exit_if_voter_already_set(&voter_address, working_set)?;
 
         self.allowed_voters
@@ -121,13 +131,14 @@ impl<C: sov_modules_api::Context> Election<C> {
         }
     }
 
-    /// Freezes the election.
+    /// Freezes the election. Must be called by the Admin.
     pub(crate) fn freeze_election(
         &self,
         context: &C,
         working_set: &mut WorkingSet<C::Storage>,
     ) -> Result<CallResponse> {
-        self.exit_if_not_admin(context, working_set)?;
+        self.roles
+            .ensure_role(ADMIN, context.sender(), working_set)?;
         self.is_frozen.set(&true, working_set);
         working_set.add_event("Election: freeze_election", "Election was frozen");
         Ok(CallResponse::default())
@@ -139,20 +150,6 @@ impl<C: sov_modules_api::Context> Election<C> {
         todo!()
     }
 
-    fn exit_if_not_admin(
-        &self,
-        context: &C,
-        working_set: &mut WorkingSet<C::Storage>,
-    ) -> Result<()> {
-        let admin = self.admin.get_or_err(working_set)?;
-
-        ensure!(
-            &admin == context.sender(),
-            "Only admin can trigger this action."
-        );
-        Ok(())
-    }
-
     fn exit_if_frozen(&self, working_set: &mut WorkingSet<C::Storage>) -> Result<()> {
         let is_frozen = self.is_frozen.get_or_err(working_set)?;
 
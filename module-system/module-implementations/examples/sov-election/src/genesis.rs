@@ -1,4 +1,4 @@
-use super::Election;
+use super::{Election, ADMIN};
 use anyhow::Result;
 use sov_state::WorkingSet;
 
@@ -8,7 +8,7 @@ impl<C: sov_modules_api::Context> Election<C> {
         config: &<Self as sov_modules_api::Module>::Config,
         working_set: &mut WorkingSet<C::Storage>,
     ) -> Result<()> {
-        self.admin.set(&config.admin, working_set);
+        self.roles.grant(ADMIN, &config.admin, working_set);
         self.is_frozen.set(&false, working_set);
 
         Ok(())
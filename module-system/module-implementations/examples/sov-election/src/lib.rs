@@ -15,6 +15,9 @@ use sov_modules_api::Error;
 use sov_modules_macros::ModuleInfo;
 use types::Voter;
 
+/// The role allowed to set the candidates, add the voters and freeze the election.
+pub const ADMIN: &str = "admin";
+
 pub struct ElectionConfig<C: sov_modules_api::Context> {
     pub admin: C::Address,
 }
@@ -24,9 +27,13 @@ pub struct Election<C: sov_modules_api::Context> {
     #[address]
     pub address: C::Address,
 
+    /// The admin of the schema version `0`, the migration to version `1` grants it the [`ADMIN`] role.
     #[state]
     pub(crate) admin: sov_state::StateValue<C::Address>,
 
+    #[state]
+    pub(crate) roles: sov_modules_api::access::Roles<C>,
+
     #[state]
     pub(crate) is_frozen: sov_state::StateValue<bool>,
 
@@ -55,6 +62,8 @@ impl<C: sov_modules_api::Context> sov_modules_api::Module for Election<C> {
 
     type CallMessage = call::CallMessage<C>;
 
+    const SCHEMA_VERSION: u64 = 1;
+
     fn genesis(
         &self,
         config: &Self::Config,
@@ -87,4 +96,27 @@ impl<C: sov_modules_api::Context> sov_modules_api::Module for Election<C> {
             Self::CallMessage::FreezeElection => Ok(self.freeze_election(context, working_set)?),
         }
     }
+
+    fn check_access(
+        &self,
+        msg: &Self::CallMessage,
+        context: &Self::Context,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<(), Error> {
+        Ok(self.roles.check_access(msg, context, working_set)?)
+    }
+
+    fn migrate(
+        &self,
+        from_version: u64,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<(), Error> {
+        // Version `1` replaced the admin address by the roles.
+        if from_version < 1 {
+            if let Some(admin) = self.admin.remove(working_set) {
+                self.roles.grant(ADMIN, &admin, working_set);
+            }
+        }
+        Ok(())
+    }
 }
@@ -1,6 +1,7 @@
-use crate::ElectionConfig;
+use crate::{ElectionConfig, ADMIN};
 
 use super::{call::CallMessage, query::GetResultResponse, types::Candidate, Election};
+use sov_modules_api::migration::migrate_module;
 use sov_modules_api::Address;
 
 use sov_modules_api::{
@@ -108,3 +109,77 @@ fn test_module<C: Context>(admin: C::Address, working_set: &mut WorkingSet<C::St
         )
     }
 }
+
+#[test]
+fn test_access_control() {
+    let admin = Address::from([1; 32]);
+    let not_admin = Address::from([2; 32]);
+    let working_set = &mut WorkingSet::new(ProverStorage::temporary());
+
+    let election = Election::<DefaultContext>::new();
+    let config = ElectionConfig {
+        admin: admin.clone(),
+    };
+    election.genesis(&config, working_set).unwrap();
+
+    let admin_context = DefaultContext::new(admin);
+    let not_admin_context = DefaultContext::new(not_admin);
+    let set_candidates = CallMessage::SetCandidates {
+        names: vec!["candidate_1".to_owned()],
+    };
+
+    election
+        .check_access(&set_candidates, &admin_context, working_set)
+        .unwrap();
+    assert!(election
+        .check_access(&set_candidates, &not_admin_context, working_set)
+        .is_err());
+    assert!(election
+        .check_access(
+            &CallMessage::FreezeElection,
+            &not_admin_context,
+            working_set
+        )
+        .is_err());
+
+    // Anyone can vote.
+    election
+        .check_access(&CallMessage::Vote(0), &not_admin_context, working_set)
+        .unwrap();
+
+    // The module rejects the message even if it is called without the access check of the runtime.
+    let err = election
+        .call(set_candidates.clone(), &not_admin_context, working_set)
+        .unwrap_err();
+    assert!(err.to_string().contains("doesn't have the role `admin`"));
+    assert!(election
+        .call(CallMessage::FreezeElection, &not_admin_context, working_set)
+        .is_err());
+
+    election
+        .call(set_candidates, &admin_context, working_set)
+        .unwrap();
+}
+
+#[test]
+fn test_migrate_admin() {
+    let admin = Address::from([1; 32]);
+    let working_set = &mut WorkingSet::new(ProverStorage::temporary());
+    let election = Election::<DefaultContext>::new();
+
+    // The state written by version 0 of the module.
+    election.admin.set(&admin, working_set);
+    election.is_frozen.set(&false, working_set);
+
+    assert!(migrate_module(&election, 0, 0, working_set).unwrap());
+    assert_eq!(election.admin.get(working_set), None);
+    assert!(election.roles.has_role(ADMIN, &admin, working_set));
+
+    election
+        .call(
+            CallMessage::AddVoter(Address::from([2; 32])),
+            &DefaultContext::new(admin),
+            working_set,
+        )
+        .unwrap();
+}
@@ -1,20 +1,20 @@
 use super::ExampleModule;
 use crate::{call, query, ExampleModuleConfig};
 
-use sov_modules_api::default_context::{DefaultContext, ZkDefaultContext};
+use sov_modules_api::default_context::{InMemoryContext, ZkDefaultContext};
 use sov_modules_api::{Address, Context};
 use sov_modules_api::{Module, ModuleInfo};
 use sov_rollup_interface::stf::Event;
-use sov_state::{ProverStorage, WorkingSet, ZkStorage};
+use sov_state::{InMemoryStorage, WorkingSet, ZkStorage};
 
 #[test]
 fn test_value_setter() {
-    let mut working_set = WorkingSet::new(ProverStorage::temporary());
+    let mut working_set = WorkingSet::new(InMemoryStorage::new());
     let admin = Address::from([1; 32]);
     // Test Native-Context
     {
         let config = ExampleModuleConfig {};
-        let context = DefaultContext::new(admin.clone());
+        let context = InMemoryContext::new(admin.clone());
         test_value_setter_helper(context, &config, &mut working_set);
     }
 
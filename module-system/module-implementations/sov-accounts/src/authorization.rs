@@ -0,0 +1,244 @@
+use crate::{Account, Accounts};
+use anyhow::{anyhow, ensure, Result};
+use sov_state::WorkingSet;
+
+/// A key allowed to sign transactions on behalf of an account.
+#[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone)]
+pub struct AuthorizedKey<C: sov_modules_api::Context> {
+    pub pub_key: C::PublicKey,
+    pub weight: u64,
+}
+
+/// Authorization rules of an account.
+/// A transaction is authorized if the weight of the key that signed it reaches the `threshold`.
+#[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone)]
+pub struct Authorization<C: sov_modules_api::Context> {
+    pub keys: Vec<AuthorizedKey<C>>,
+    pub threshold: u64,
+}
+
+impl<C: sov_modules_api::Context> Authorization<C> {
+    /// Rules of an account controlled only by its root key.
+    pub(crate) fn single_key(root_pub_key: C::PublicKey) -> Self {
+        Self {
+            keys: vec![AuthorizedKey {
+                pub_key: root_pub_key,
+                weight: 1,
+            }],
+            threshold: 1,
+        }
+    }
+
+    pub fn weight_of(&self, pub_key: &C::PublicKey) -> Option<u64> {
+        self.keys
+            .iter()
+            .find(|key| &key.pub_key == pub_key)
+            .map(|key| key.weight)
+    }
+
+    /// Returns the highest weight among the keys. A transaction is signed by a single key,
+    /// so the account is locked if it is below the threshold.
+    pub fn max_weight(&self) -> u64 {
+        self.keys.iter().map(|key| key.weight).max().unwrap_or(0)
+    }
+}
+
+/// A key rotation that takes effect once the rollup reaches `activation_height`.
+#[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone)]
+pub struct PendingRotation<C: sov_modules_api::Context> {
+    pub old_pub_key: C::PublicKey,
+    pub new_pub_key: C::PublicKey,
+    pub activation_height: u64,
+}
+
+/// A key that can sign a restricted set of messages on behalf of an account.
+#[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone)]
+pub struct SessionKey<C: sov_modules_api::Context> {
+    /// The account on whose behalf the session key signs.
+    pub addr: C::Address,
+    /// The serialized runtime message must start with one of these prefixes.
+    /// Runtime messages are borsh encoded enums, so the first byte selects the module
+    /// and the second byte (usually) selects the call message of that module.
+    pub allowed_message_prefixes: Vec<Vec<u8>>,
+    /// The last batch height at which the session key is valid.
+    pub expires_at: u64,
+}
+
+impl<C: sov_modules_api::Context> SessionKey<C> {
+    pub fn allows(&self, runtime_msg: &[u8]) -> bool {
+        self.allowed_message_prefixes
+            .iter()
+            .any(|prefix| runtime_msg.starts_with(prefix))
+    }
+}
+
+impl<C: sov_modules_api::Context> Accounts<C> {
+    /// Returns the address on whose behalf `pub_key` signed `runtime_msg`, enforcing
+    /// the authorization rules of the account. A new account is created for unknown keys.
+    pub(crate) fn authorize(
+        &self,
+        pub_key: &C::PublicKey,
+        runtime_msg: &[u8],
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<C::Address> {
+        let height = self.batch_height.get(working_set).unwrap_or_default();
+
+        if let Some(session_key) = self.session_keys.get(pub_key, working_set) {
+            ensure!(height <= session_key.expires_at, "Session key expired");
+            ensure!(
+                session_key.allows(runtime_msg),
+                "Message is not allowed for the session key"
+            );
+            return Ok(session_key.addr);
+        }
+
+        // The new key of a pending rotation signs for its account once the rotation is due.
+        let addr = match self.signer_address(pub_key, working_set) {
+            Some(addr) => addr,
+            None => match self.rotation_targets.get(pub_key, working_set) {
+                Some(addr) => addr,
+                None => {
+                    return Ok(self
+                        .create_default_account(pub_key.clone(), working_set)?
+                        .addr)
+                }
+            },
+        };
+
+        self.apply_due_rotation(&addr, height, working_set)?;
+
+        let authorization = self.authorization(&addr, working_set)?;
+        let weight = authorization
+            .weight_of(pub_key)
+            .ok_or_else(|| anyhow!("Key is not authorized to sign for {}", addr))?;
+        ensure!(
+            weight >= authorization.threshold,
+            "Key weight {weight} is below the threshold {}",
+            authorization.threshold
+        );
+
+        Ok(addr)
+    }
+
+    /// Returns the authorization rules of the account at `addr`.
+    pub(crate) fn authorization(
+        &self,
+        addr: &C::Address,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<Authorization<C>> {
+        match self.authorizations.get(addr, working_set) {
+            Some(authorization) => Ok(authorization),
+            None => {
+                let root_pub_key = self.public_keys.get_or_err(addr, working_set)?;
+                Ok(Authorization::single_key(root_pub_key))
+            }
+        }
+    }
+
+    /// Returns the root public key of the account at `addr` together with the account.
+    pub(crate) fn root_account(
+        &self,
+        addr: &C::Address,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<(C::PublicKey, Account<C>)> {
+        let root_pub_key = self.public_keys.get_or_err(addr, working_set)?;
+        let account = self.accounts.get_or_err(&root_pub_key, working_set)?;
+        Ok((root_pub_key, account))
+    }
+
+    /// Checks whether `pub_key` is already used by any account.
+    pub(crate) fn is_key_known(
+        &self,
+        pub_key: &C::PublicKey,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> bool {
+        self.signer_address(pub_key, working_set).is_some()
+            || self.session_keys.get(pub_key, working_set).is_some()
+    }
+
+    /// Replaces `old_pub_key` with `new_pub_key` among the keys of the account at `addr`.
+    /// The new key inherits the weight of the old one.
+    pub(crate) fn replace_key(
+        &self,
+        addr: &C::Address,
+        old_pub_key: &C::PublicKey,
+        new_pub_key: &C::PublicKey,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<()> {
+        if let Some(mut authorization) = self.authorizations.get(addr, working_set) {
+            let key = authorization
+                .keys
+                .iter_mut()
+                .find(|key| &key.pub_key == old_pub_key)
+                .ok_or_else(|| anyhow!("Key is not authorized to sign for {}", addr))?;
+            key.pub_key = new_pub_key.clone();
+            self.authorizations.set(addr, &authorization, working_set);
+        }
+
+        let root_pub_key = self.public_keys.get_or_err(addr, working_set)?;
+        if &root_pub_key == old_pub_key {
+            let account = self.accounts.remove_or_err(old_pub_key, working_set)?;
+            // Sanity check
+            ensure!(addr == &account.addr, "Inconsistent account data");
+
+            self.accounts.set(new_pub_key, &account, working_set);
+            self.public_keys.set(addr, new_pub_key, working_set);
+        } else {
+            self.signers.remove_or_err(old_pub_key, working_set)?;
+            self.signers.set(new_pub_key, addr, working_set);
+        }
+
+        Ok(())
+    }
+
+    /// Removes the pending rotation of the account at `addr`, if any.
+    pub(crate) fn remove_pending_rotation(
+        &self,
+        addr: &C::Address,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Option<PendingRotation<C>> {
+        let rotation = self.pending_rotations.remove(addr, working_set)?;
+        self.rotation_targets
+            .delete(&rotation.new_pub_key, working_set);
+        Some(rotation)
+    }
+
+    fn signer_address(
+        &self,
+        pub_key: &C::PublicKey,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Option<C::Address> {
+        match self.accounts.get(pub_key, working_set) {
+            Some(account) => Some(account.addr),
+            None => self.signers.get(pub_key, working_set),
+        }
+    }
+
+    fn apply_due_rotation(
+        &self,
+        addr: &C::Address,
+        height: u64,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<()> {
+        let rotation = match self.pending_rotations.get(addr, working_set) {
+            Some(rotation) if rotation.activation_height <= height => rotation,
+            _ => return Ok(()),
+        };
+        self.remove_pending_rotation(addr, working_set);
+
+        // The old key may have been removed, or the new key claimed by another account, in the meantime.
+        let authorization = self.authorization(addr, working_set)?;
+        if authorization.weight_of(&rotation.old_pub_key).is_none()
+            || self.is_key_known(&rotation.new_pub_key, working_set)
+        {
+            return Ok(());
+        }
+
+        self.replace_key(
+            addr,
+            &rotation.old_pub_key,
+            &rotation.new_pub_key,
+            working_set,
+        )
+    }
+}
@@ -1,10 +1,35 @@
-use crate::Accounts;
-use anyhow::{ensure, Result};
+use crate::{Accounts, AuthorizedKey, PendingRotation, SessionKey};
+use anyhow::{anyhow, ensure, Result};
 use sov_modules_api::CallResponse;
+use sov_modules_api::Hasher;
 use sov_modules_api::Signature;
 use sov_state::WorkingSet;
 
-pub const UPDATE_ACCOUNT_MSG: [u8; 32] = [1; 32];
+/// Domain separator of the message signed by a key to prove that it is in possession of its owner.
+pub const UPDATE_ACCOUNT_DOMAIN: &[u8] = b"sov-accounts/update-account";
+
+/// Returns the message a new key has to sign before it is associated with the account at `addr`.
+///
+/// The message is bound to the account, to the `nonce_lane` of the transaction and its `nonce`,
+/// and to the `chain_id` of the rollup, so the signature can't be replayed for another account,
+/// rollup, lane, or later on the same account.
+/// The `nonce` is the nonce of the lane when the call message is executed.
+/// Since the nonce is increased before dispatching, it is `n + 1` for a transaction sent
+/// with nonce `n`. Transactions without a lane are sent in the main lane `0`.
+pub fn update_account_msg<C: sov_modules_api::Context>(
+    addr: &C::Address,
+    nonce_lane: u64,
+    nonce: u64,
+    chain_id: u64,
+) -> [u8; 32] {
+    let mut hasher = C::Hasher::new();
+    hasher.update(UPDATE_ACCOUNT_DOMAIN);
+    hasher.update(addr.as_ref());
+    hasher.update(&nonce_lane.to_le_bytes());
+    hasher.update(&nonce.to_le_bytes());
+    hasher.update(&chain_id.to_le_bytes());
+    hasher.finalize()
+}
 
 #[cfg_attr(
     feature = "native",
@@ -14,8 +39,63 @@ pub const UPDATE_ACCOUNT_MSG: [u8; 32] = [1; 32];
 #[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone)]
 pub enum CallMessage<C: sov_modules_api::Context> {
     // Updates a PublicKey for the corresponding Account.
-    // The sender must be in possession of the new PublicKey.
+    // The sender must be in possession of the new PublicKey,
+    // and sign the message returned by `update_account_msg` with it.
     UpdatePublicKey(C::PublicKey, C::Signature),
+
+    /// Authorizes an additional key to sign on behalf of the sender's account.
+    AddKey {
+        /// The new key.
+        pub_key: C::PublicKey,
+        /// The weight of the new key.
+        weight: u64,
+        /// Proof that the sender is in possession of the new key.
+        signature: C::Signature,
+    },
+
+    /// Revokes an authorized key of the sender's account. The root key can't be removed.
+    RemoveKey {
+        /// The key to remove.
+        pub_key: C::PublicKey,
+    },
+
+    /// Sets the minimum key weight required to authorize a transaction.
+    SetThreshold {
+        /// The new threshold.
+        threshold: u64,
+    },
+
+    /// Schedules the replacement of an authorized key, which takes effect
+    /// once the key rotation delay has passed.
+    ScheduleKeyRotation {
+        /// The key to replace.
+        old_pub_key: C::PublicKey,
+        /// The replacement key.
+        new_pub_key: C::PublicKey,
+        /// Proof that the sender is in possession of the replacement key.
+        signature: C::Signature,
+    },
+
+    /// Cancels the pending key rotation of the sender's account.
+    CancelKeyRotation,
+
+    /// Registers a session key restricted to a set of runtime messages.
+    AddSessionKey {
+        /// The session key.
+        pub_key: C::PublicKey,
+        /// Prefixes of the serialized runtime messages the session key is allowed to sign.
+        allowed_message_prefixes: Vec<Vec<u8>>,
+        /// The last batch height at which the session key is valid.
+        expires_at: u64,
+        /// Proof that the sender is in possession of the session key.
+        signature: C::Signature,
+    },
+
+    /// Revokes a session key of the sender's account.
+    RevokeSessionKey {
+        /// The session key to revoke.
+        pub_key: C::PublicKey,
+    },
 }
 
 impl<C: sov_modules_api::Context> Accounts<C> {
@@ -26,34 +106,219 @@ impl<C: sov_modules_api::Context> Accounts<C> {
         context: &C,
         working_set: &mut WorkingSet<C::Storage>,
     ) -> Result<CallResponse> {
-        self.exit_if_account_exists(&new_pub_key, working_set)?;
+        self.exit_if_key_exists(&new_pub_key, working_set)?;
 
         let pub_key = self.public_keys.get_or_err(context.sender(), working_set)?;
 
-        let account = self.accounts.remove_or_err(&pub_key, working_set)?;
-        // Sanity check
+        // Proof that the sender is in possession of the `new_pub_key`.
+        self.verify_key_possession(&new_pub_key, &signature, context, working_set)?;
+
+        // Update the public key (account data remains the same).
+        self.replace_key(context.sender(), &pub_key, &new_pub_key, working_set)?;
+        Ok(CallResponse::default())
+    }
+
+    pub(crate) fn add_key(
+        &self,
+        pub_key: C::PublicKey,
+        weight: u64,
+        signature: C::Signature,
+        context: &C,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<CallResponse> {
+        self.exit_if_key_exists(&pub_key, working_set)?;
+        ensure!(weight > 0, "Key weight must be positive");
+
+        // Proof that the sender is in possession of the `pub_key`.
+        self.verify_key_possession(&pub_key, &signature, context, working_set)?;
+
+        let mut authorization = self.authorization(context.sender(), working_set)?;
+        authorization.keys.push(AuthorizedKey {
+            pub_key: pub_key.clone(),
+            weight,
+        });
+
+        self.signers.set(&pub_key, context.sender(), working_set);
+        self.authorizations
+            .set(context.sender(), &authorization, working_set);
+        Ok(CallResponse::default())
+    }
+
+    pub(crate) fn remove_key(
+        &self,
+        pub_key: C::PublicKey,
+        context: &C,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<CallResponse> {
+        let root_pub_key = self.public_keys.get_or_err(context.sender(), working_set)?;
+        ensure!(root_pub_key != pub_key, "The root key can't be removed");
+
+        let mut authorization = self.authorization(context.sender(), working_set)?;
+        let index = authorization
+            .keys
+            .iter()
+            .position(|key| key.pub_key == pub_key)
+            .ok_or_else(|| anyhow!("Key is not authorized to sign for {}", context.sender()))?;
+        authorization.keys.remove(index);
+
+        ensure!(
+            authorization.max_weight() >= authorization.threshold,
+            "Removing the key would lock the account"
+        );
+
+        self.signers.delete(&pub_key, working_set);
+        self.authorizations
+            .set(context.sender(), &authorization, working_set);
+        Ok(CallResponse::default())
+    }
+
+    pub(crate) fn set_threshold(
+        &self,
+        threshold: u64,
+        context: &C,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<CallResponse> {
+        ensure!(threshold > 0, "Threshold must be positive");
+
+        let mut authorization = self.authorization(context.sender(), working_set)?;
+        ensure!(
+            threshold <= authorization.max_weight(),
+            "Threshold exceeds the weight of every account key"
+        );
+        authorization.threshold = threshold;
+
+        self.authorizations
+            .set(context.sender(), &authorization, working_set);
+        Ok(CallResponse::default())
+    }
+
+    pub(crate) fn schedule_key_rotation(
+        &self,
+        old_pub_key: C::PublicKey,
+        new_pub_key: C::PublicKey,
+        signature: C::Signature,
+        context: &C,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<CallResponse> {
+        self.exit_if_key_exists(&new_pub_key, working_set)?;
+
+        let authorization = self.authorization(context.sender(), working_set)?;
         ensure!(
-            context.sender() == &account.addr,
-            "Inconsistent account data"
+            authorization.weight_of(&old_pub_key).is_some(),
+            "Key is not authorized to sign for {}",
+            context.sender()
         );
 
         // Proof that the sender is in possession of the `new_pub_key`.
-        signature.verify(&new_pub_key, UPDATE_ACCOUNT_MSG)?;
+        self.verify_key_possession(&new_pub_key, &signature, context, working_set)?;
 
-        // Update the public key (account data remains the same).
-        self.accounts.set(&new_pub_key, &account, working_set);
-        self.public_keys
-            .set(context.sender(), &new_pub_key, working_set);
+        let height = self.batch_height.get(working_set).unwrap_or_default();
+        let delay = self.key_rotation_delay.get(working_set).unwrap_or_default();
+        let rotation = PendingRotation {
+            old_pub_key,
+            new_pub_key,
+            activation_height: height.saturating_add(delay),
+        };
+
+        // A new rotation replaces the pending one.
+        self.remove_pending_rotation(context.sender(), working_set);
+        self.rotation_targets
+            .set(&rotation.new_pub_key, context.sender(), working_set);
+        self.pending_rotations
+            .set(context.sender(), &rotation, working_set);
+        Ok(CallResponse::default())
+    }
+
+    pub(crate) fn cancel_key_rotation(
+        &self,
+        context: &C,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<CallResponse> {
+        self.remove_pending_rotation(context.sender(), working_set)
+            .ok_or_else(|| anyhow!("No pending key rotation for {}", context.sender()))?;
+        Ok(CallResponse::default())
+    }
+
+    pub(crate) fn add_session_key(
+        &self,
+        pub_key: C::PublicKey,
+        allowed_message_prefixes: Vec<Vec<u8>>,
+        expires_at: u64,
+        signature: C::Signature,
+        context: &C,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<CallResponse> {
+        self.exit_if_key_exists(&pub_key, working_set)?;
+        ensure!(
+            !allowed_message_prefixes.is_empty(),
+            "Session key must be allowed to sign at least one message"
+        );
+
+        let height = self.batch_height.get(working_set).unwrap_or_default();
+        ensure!(expires_at >= height, "Session key is already expired");
+
+        // Proof that the sender is in possession of the `pub_key`.
+        self.verify_key_possession(&pub_key, &signature, context, working_set)?;
+
+        let session_key = SessionKey {
+            addr: context.sender().clone(),
+            allowed_message_prefixes,
+            expires_at,
+        };
+
+        self.session_keys.set(&pub_key, &session_key, working_set);
         Ok(CallResponse::default())
     }
 
-    fn exit_if_account_exists(
+    pub(crate) fn revoke_session_key(
+        &self,
+        pub_key: C::PublicKey,
+        context: &C,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<CallResponse> {
+        let session_key = self.session_keys.get_or_err(&pub_key, working_set)?;
+        ensure!(
+            &session_key.addr == context.sender(),
+            "Session key belongs to another account"
+        );
+
+        self.session_keys.delete(&pub_key, working_set);
+        Ok(CallResponse::default())
+    }
+
+    /// Checks that `signature` is a signature of `pub_key` over the `update_account_msg` of the sender.
+    fn verify_key_possession(
+        &self,
+        pub_key: &C::PublicKey,
+        signature: &C::Signature,
+        context: &C,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<()> {
+        let nonce_lane = context.nonce_lane();
+        let nonce = match nonce_lane {
+            0 => self.root_account(context.sender(), working_set)?.1.nonce,
+            _ => self
+                .lane_nonces
+                .get(&(context.sender().clone(), nonce_lane), working_set)
+                .unwrap_or_default(),
+        };
+        let chain_id = self.chain_id.get(working_set).unwrap_or_default();
+        let msg = update_account_msg::<C>(context.sender(), nonce_lane, nonce, chain_id);
+        signature.verify(pub_key, msg)?;
+        Ok(())
+    }
+
+    fn exit_if_key_exists(
         &self,
         new_pub_key: &C::PublicKey,
         working_set: &mut WorkingSet<C::Storage>,
     ) -> Result<()> {
         anyhow::ensure!(
-            self.accounts.get(new_pub_key, working_set).is_none(),
+            !self.is_key_known(new_pub_key, working_set)
+                && self
+                    .rotation_targets
+                    .get(new_pub_key, working_set)
+                    .is_none(),
             "New PublicKey already exists"
         );
         Ok(())
@@ -17,6 +17,10 @@ impl<C: sov_modules_api::Context> Accounts<C> {
             self.create_default_account(pub_key.clone(), working_set)?;
         }
 
+        self.key_rotation_delay
+            .set(&config.key_rotation_delay, working_set);
+        self.chain_id.set(&config.chain_id, working_set);
+
         Ok(())
     }
 
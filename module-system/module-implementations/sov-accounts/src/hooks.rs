@@ -1,5 +1,5 @@
-use crate::Accounts;
-use sov_modules_api::hooks::TxHooks;
+use crate::{Accounts, MAX_NONCE_LANES};
+use sov_modules_api::hooks::{ApplyBlobHooks, TxHooks};
 use sov_modules_api::transaction::Transaction;
 use sov_modules_api::Context;
 
@@ -14,31 +14,105 @@ impl<C: Context> TxHooks for Accounts<C> {
         tx: Transaction<C>,
         working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
     ) -> anyhow::Result<<Self::Context as Spec>::Address> {
-        let pub_key = tx.pub_key().clone();
+        let addr = self.authorize(tx.pub_key(), tx.runtime_msg(), working_set)?;
 
-        let acc = match self.accounts.get(&pub_key, working_set) {
-            Some(acc) => Ok(acc),
-            None => self.create_default_account(pub_key, working_set),
-        }?;
+        // The nonce is increased before dispatching, because the transaction may replace
+        // the key it was signed with. If the transaction is reverted, so is the increase.
+        self.use_nonce(&addr, tx.nonce_lane(), tx.nonce(), working_set)?;
 
-        let tx_nonce = tx.nonce();
-        let acc_nonce = acc.nonce;
+        Ok(addr)
+    }
+
+    /// A session key allowed to call this module could manage the keys of the account.
+    fn pre_dispatch_call_hook(
+        &self,
+        tx: &Transaction<Self::Context>,
+        module_address: &<Self::Context as Spec>::Address,
+        working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
         anyhow::ensure!(
-            acc_nonce == tx_nonce,
-            "Tx bad nonce, expected: {acc_nonce}, but found: {tx_nonce}",
+            module_address != &self.address
+                || self.session_keys.get(tx.pub_key(), working_set).is_none(),
+            "Session keys can't be used to call the accounts module"
         );
-
-        Ok(acc.addr)
+        Ok(())
     }
 
     fn post_dispatch_tx_hook(
         &self,
-        tx: &Transaction<Self::Context>,
-        working_set: &mut WorkingSet<<Self::Context as sov_modules_api::Spec>::Storage>,
+        _tx: &Transaction<Self::Context>,
+        _working_set: &mut WorkingSet<<Self::Context as sov_modules_api::Spec>::Storage>,
     ) -> anyhow::Result<()> {
-        let mut account = self.accounts.get_or_err(tx.pub_key(), working_set)?;
-        account.nonce += 1;
-        self.accounts.set(tx.pub_key(), &account, working_set);
+        Ok(())
+    }
+}
+
+impl<C: Context> ApplyBlobHooks for Accounts<C> {
+    type Context = C;
+    type BlobResult = ();
+
+    /// Advances the batch height used for key rotation timelocks and session key expiration.
+    fn begin_blob_hook(
+        &self,
+        _sequencer: &[u8],
+        _raw_blob: &[u8],
+        working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
+        let height = self.batch_height.get(working_set).unwrap_or_default();
+        self.batch_height.set(&(height + 1), working_set);
+        Ok(())
+    }
+
+    fn end_blob_hook(
+        &self,
+        _result: Self::BlobResult,
+        _working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+impl<C: Context> Accounts<C> {
+    /// Checks that `tx_nonce` is the next nonce of the account in `nonce_lane` and increases it.
+    fn use_nonce(
+        &self,
+        addr: &C::Address,
+        nonce_lane: u64,
+        tx_nonce: u64,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> anyhow::Result<()> {
+        if nonce_lane == 0 {
+            let (root_pub_key, mut account) = self.root_account(addr, working_set)?;
+            let acc_nonce = account.nonce;
+            anyhow::ensure!(
+                acc_nonce == tx_nonce,
+                "Tx bad nonce, expected: {acc_nonce}, but found: {tx_nonce}",
+            );
+
+            account.nonce += 1;
+            self.accounts.set(&root_pub_key, &account, working_set);
+        } else {
+            let lane = (addr.clone(), nonce_lane);
+            let stored_lane_nonce = self.lane_nonces.get(&lane, working_set);
+            let lane_nonce = stored_lane_nonce.unwrap_or_default();
+            anyhow::ensure!(
+                lane_nonce == tx_nonce,
+                "Tx bad nonce in lane {nonce_lane}, expected: {lane_nonce}, but found: {tx_nonce}",
+            );
+
+            // Every lane is stored in the state, so their number is bounded.
+            if stored_lane_nonce.is_none() {
+                let lane_count = self.lane_counts.get(addr, working_set).unwrap_or_default();
+                anyhow::ensure!(
+                    lane_count < MAX_NONCE_LANES,
+                    "Account {addr} already uses the maximum of {MAX_NONCE_LANES} nonce lanes",
+                );
+                self.lane_counts.set(addr, &(lane_count + 1), working_set);
+            }
+
+            self.lane_nonces.set(&lane, &(lane_nonce + 1), working_set);
+        }
+
         Ok(())
     }
 }
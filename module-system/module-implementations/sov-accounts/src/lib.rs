@@ -1,5 +1,6 @@
 pub mod hooks;
 
+mod authorization;
 pub mod call;
 pub mod genesis;
 #[cfg(feature = "native")]
@@ -7,13 +8,22 @@ pub mod query;
 #[cfg(test)]
 mod tests;
 
+pub use authorization::{Authorization, AuthorizedKey, PendingRotation, SessionKey};
 use sov_modules_api::Error;
 use sov_modules_macros::ModuleInfo;
 use sov_state::WorkingSet;
 
+/// Maximum number of nonce lanes an account can use besides the main lane.
+/// This is a lifetime limit: lanes are never closed, so a lane counts once it was used by a transaction.
+pub const MAX_NONCE_LANES: u64 = 16;
+
 /// Initial configuration for sov-bank module.
 pub struct AccountConfig<C: sov_modules_api::Context> {
     pub pub_keys: Vec<C::PublicKey>,
+    /// Number of batches after which a scheduled key rotation takes effect.
+    pub key_rotation_delay: u64,
+    /// Identifier of the rollup, signed when proving possession of a new key.
+    pub chain_id: u64,
 }
 
 #[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Copy, Clone)]
@@ -27,11 +37,55 @@ pub struct Accounts<C: sov_modules_api::Context> {
     #[address]
     pub address: C::Address,
 
+    /// Mapping from an address to the root public key of the account.
     #[state]
     pub(crate) public_keys: sov_state::StateMap<C::Address, C::PublicKey>,
 
+    /// Mapping from a root public key to the account.
     #[state]
     pub(crate) accounts: sov_state::StateMap<C::PublicKey, Account<C>>,
+
+    /// Next expected nonce of an account in a nonce lane other than the main lane `0`,
+    /// whose nonce is stored in the `Account` itself.
+    #[state]
+    pub(crate) lane_nonces: sov_state::StateMap<(C::Address, u64), u64>,
+
+    /// Number of nonce lanes other than the main lane used by an account.
+    #[state]
+    pub(crate) lane_counts: sov_state::StateMap<C::Address, u64>,
+
+    /// Authorization rules of accounts with more than one key.
+    /// Accounts without an entry are controlled by their root key only.
+    #[state]
+    pub(crate) authorizations: sov_state::StateMap<C::Address, Authorization<C>>,
+
+    /// Mapping from a non-root authorized key to the address of its account.
+    #[state]
+    pub(crate) signers: sov_state::StateMap<C::PublicKey, C::Address>,
+
+    /// Session keys and their restrictions.
+    #[state]
+    pub(crate) session_keys: sov_state::StateMap<C::PublicKey, SessionKey<C>>,
+
+    /// Key rotations waiting for their timelock to expire.
+    #[state]
+    pub(crate) pending_rotations: sov_state::StateMap<C::Address, PendingRotation<C>>,
+
+    /// Mapping from the new key of a pending rotation to the address of its account.
+    #[state]
+    pub(crate) rotation_targets: sov_state::StateMap<C::PublicKey, C::Address>,
+
+    /// Number of batches processed by the rollup, used to enforce timelocks and expirations.
+    #[state]
+    pub(crate) batch_height: sov_state::StateValue<u64>,
+
+    /// Number of batches after which a scheduled key rotation takes effect.
+    #[state]
+    pub(crate) key_rotation_delay: sov_state::StateValue<u64>,
+
+    /// Identifier of the rollup, signed when proving possession of a new key.
+    #[state]
+    pub(crate) chain_id: sov_state::StateValue<u64>,
 }
 
 impl<C: sov_modules_api::Context> sov_modules_api::Module for Accounts<C> {
@@ -59,6 +113,54 @@ impl<C: sov_modules_api::Context> sov_modules_api::Module for Accounts<C> {
             call::CallMessage::UpdatePublicKey(new_pub_key, sig) => {
                 Ok(self.update_public_key(new_pub_key, sig, context, working_set)?)
             }
+
+            call::CallMessage::AddKey {
+                pub_key,
+                weight,
+                signature,
+            } => Ok(self.add_key(pub_key, weight, signature, context, working_set)?),
+
+            call::CallMessage::RemoveKey { pub_key } => {
+                Ok(self.remove_key(pub_key, context, working_set)?)
+            }
+
+            call::CallMessage::SetThreshold { threshold } => {
+                Ok(self.set_threshold(threshold, context, working_set)?)
+            }
+
+            call::CallMessage::ScheduleKeyRotation {
+                old_pub_key,
+                new_pub_key,
+                signature,
+            } => Ok(self.schedule_key_rotation(
+                old_pub_key,
+                new_pub_key,
+                signature,
+                context,
+                working_set,
+            )?),
+
+            call::CallMessage::CancelKeyRotation => {
+                Ok(self.cancel_key_rotation(context, working_set)?)
+            }
+
+            call::CallMessage::AddSessionKey {
+                pub_key,
+                allowed_message_prefixes,
+                expires_at,
+                signature,
+            } => Ok(self.add_session_key(
+                pub_key,
+                allowed_message_prefixes,
+                expires_at,
+                signature,
+                context,
+                working_set,
+            )?),
+
+            call::CallMessage::RevokeSessionKey { pub_key } => {
+                Ok(self.revoke_session_key(pub_key, context, working_set)?)
+            }
         }
     }
 }
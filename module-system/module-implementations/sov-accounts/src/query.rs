@@ -23,3 +23,27 @@ impl<C: sov_modules_api::Context> Accounts<C> {
         }
     }
 }
+
+impl<C: sov_modules_api::Context> Accounts<C> {
+    /// Returns the next expected nonce of the account at `addr` in `nonce_lane`.
+    pub fn get_lane_nonce(
+        &self,
+        addr: C::Address,
+        nonce_lane: u64,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Option<u64> {
+        if nonce_lane == 0 {
+            return self
+                .root_account(&addr, working_set)
+                .ok()
+                .map(|(_, account)| account.nonce);
+        }
+
+        self.public_keys.get(&addr, working_set)?;
+        Some(
+            self.lane_nonces
+                .get(&(addr, nonce_lane), working_set)
+                .unwrap_or_default(),
+        )
+    }
+}
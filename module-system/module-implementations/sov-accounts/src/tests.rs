@@ -4,7 +4,10 @@ use crate::{
     AccountConfig, Accounts,
 };
 use sov_modules_api::{
-    default_context::DefaultContext, default_signature::private_key::DefaultPrivateKey,
+    default_context::DefaultContext,
+    default_signature::private_key::DefaultPrivateKey,
+    hooks::{ApplyBlobHooks, TxHooks},
+    transaction::Transaction,
     AddressBech32, Context, Module, ModuleInfo, PublicKey, Spec,
 };
 use sov_state::{ProverStorage, WorkingSet};
@@ -19,6 +22,8 @@ fn test_config_account() {
 
     let account_config = AccountConfig::<C> {
         pub_keys: vec![init_pub_key.clone()],
+        key_rotation_delay: 0,
+        chain_id: 0,
     };
 
     let accounts = &mut Accounts::<C>::new();
@@ -71,7 +76,7 @@ fn test_update_account() {
     {
         let priv_key = DefaultPrivateKey::generate();
         let new_pub_key = priv_key.pub_key();
-        let sig = priv_key.sign(call::UPDATE_ACCOUNT_MSG);
+        let sig = sign_update_account_msg(accounts, &priv_key, &sender_addr, native_working_set);
         accounts
             .call(
                 call::CallMessage::<C>::UpdatePublicKey(new_pub_key.clone(), sig),
@@ -112,7 +117,12 @@ fn test_update_account_fails() {
 
     let priv_key = DefaultPrivateKey::generate();
     let sender_2 = priv_key.pub_key();
-    let sig_2 = priv_key.sign(call::UPDATE_ACCOUNT_MSG);
+    let sig_2 = sign_update_account_msg(
+        accounts,
+        &priv_key,
+        sender_context_1.sender(),
+        native_working_set,
+    );
 
     accounts
         .create_default_account(sender_2.clone(), native_working_set)
@@ -143,7 +153,7 @@ fn test_get_acc_after_pub_key_update() {
 
     let priv_key = DefaultPrivateKey::generate();
     let new_pub_key = priv_key.pub_key();
-    let sig = priv_key.sign(call::UPDATE_ACCOUNT_MSG);
+    let sig = sign_update_account_msg(accounts, &priv_key, &sender_1_addr, native_working_set);
     accounts
         .call(
             call::CallMessage::<C>::UpdatePublicKey(new_pub_key.clone(), sig),
@@ -160,6 +170,435 @@ fn test_get_acc_after_pub_key_update() {
     assert_eq!(acc.addr, sender_1_addr)
 }
 
+#[test]
+fn test_update_account_signature_replay() {
+    const CHAIN_ID: u64 = 7;
+    let native_working_set = &mut WorkingSet::new(ProverStorage::temporary());
+    let accounts = &mut Accounts::<C>::new();
+
+    let root_priv_key = DefaultPrivateKey::generate();
+    let root_addr = root_priv_key.pub_key().to_address::<<C as Spec>::Address>();
+    let context = C::new(root_addr.clone());
+    let account_config = AccountConfig::<C> {
+        pub_keys: vec![root_priv_key.pub_key()],
+        key_rotation_delay: 0,
+        chain_id: CHAIN_ID,
+    };
+    accounts
+        .genesis(&account_config, native_working_set)
+        .unwrap();
+
+    let new_priv_key = DefaultPrivateKey::generate();
+
+    // A signature for another chain is rejected.
+    let sig = new_priv_key.sign(call::update_account_msg::<C>(
+        &root_addr,
+        0,
+        0,
+        CHAIN_ID + 1,
+    ));
+    assert!(accounts
+        .call(
+            call::CallMessage::UpdatePublicKey(new_priv_key.pub_key(), sig),
+            &context,
+            native_working_set,
+        )
+        .is_err());
+
+    // A signature for another account is rejected.
+    let other_addr = DefaultPrivateKey::generate()
+        .pub_key()
+        .to_address::<<C as Spec>::Address>();
+    let sig = new_priv_key.sign(call::update_account_msg::<C>(&other_addr, 0, 0, CHAIN_ID));
+    assert!(accounts
+        .call(
+            call::CallMessage::UpdatePublicKey(new_priv_key.pub_key(), sig),
+            &context,
+            native_working_set,
+        )
+        .is_err());
+
+    // A signature for a stale nonce is rejected.
+    let stale_sig = new_priv_key.sign(call::update_account_msg::<C>(&root_addr, 0, 0, CHAIN_ID));
+    let tx = Transaction::new_signed_tx(&root_priv_key, vec![], 0);
+    accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+    assert!(accounts
+        .call(
+            call::CallMessage::UpdatePublicKey(new_priv_key.pub_key(), stale_sig),
+            &context,
+            native_working_set,
+        )
+        .is_err());
+
+    // A signature for the main lane is rejected in a transaction of another lane.
+    let sig = new_priv_key.sign(call::update_account_msg::<C>(&root_addr, 0, 1, CHAIN_ID));
+    let tx = Transaction::new_signed_tx_in_lane(&root_priv_key, vec![], 5, 0);
+    accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+    assert!(accounts
+        .call(
+            call::CallMessage::UpdatePublicKey(new_priv_key.pub_key(), sig.clone()),
+            &context.clone().with_nonce_lane(5),
+            native_working_set,
+        )
+        .is_err());
+
+    let tx = Transaction::new_signed_tx(&root_priv_key, vec![], 1);
+    accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+    let sig = new_priv_key.sign(call::update_account_msg::<C>(&root_addr, 0, 2, CHAIN_ID));
+    accounts
+        .call(
+            call::CallMessage::UpdatePublicKey(new_priv_key.pub_key(), sig),
+            &context,
+            native_working_set,
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_nonce_is_shared_by_account_keys() {
+    let native_working_set = &mut WorkingSet::new(ProverStorage::temporary());
+    let accounts = &mut Accounts::<C>::new();
+
+    let root_priv_key = DefaultPrivateKey::generate();
+    let root_addr = root_priv_key.pub_key().to_address::<<C as Spec>::Address>();
+    let context = C::new(root_addr.clone());
+
+    let tx = Transaction::new_signed_tx(&root_priv_key, vec![], 0);
+    let addr = accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+    assert_eq!(addr, root_addr);
+
+    let second_priv_key = DefaultPrivateKey::generate();
+    add_key(accounts, &second_priv_key, 1, &context, native_working_set);
+
+    // The nonce of the account was increased by the transaction signed with the root key.
+    let tx = Transaction::new_signed_tx(&second_priv_key, vec![], 0);
+    assert!(accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .is_err());
+
+    let tx = Transaction::new_signed_tx(&second_priv_key, vec![], 1);
+    let addr = accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+    assert_eq!(addr, root_addr);
+}
+
+#[test]
+fn test_key_weights() {
+    let native_working_set = &mut WorkingSet::new(ProverStorage::temporary());
+    let accounts = &mut Accounts::<C>::new();
+
+    let root_pub_key = DefaultPrivateKey::generate().pub_key();
+    let root_addr = root_pub_key.to_address::<<C as Spec>::Address>();
+    let context = C::new(root_addr.clone());
+    accounts
+        .create_default_account(root_pub_key, native_working_set)
+        .unwrap();
+
+    let light_priv_key = DefaultPrivateKey::generate();
+    let heavy_priv_key = DefaultPrivateKey::generate();
+    add_key(accounts, &light_priv_key, 1, &context, native_working_set);
+    add_key(accounts, &heavy_priv_key, 2, &context, native_working_set);
+
+    // The threshold can't exceed the weight of the heaviest key, even if the keys together reach it,
+    // because a transaction is signed by a single key.
+    for threshold in [3, 5] {
+        assert!(accounts
+            .call(
+                call::CallMessage::SetThreshold { threshold },
+                &context,
+                native_working_set,
+            )
+            .is_err());
+    }
+
+    accounts
+        .call(
+            call::CallMessage::SetThreshold { threshold: 2 },
+            &context,
+            native_working_set,
+        )
+        .unwrap();
+
+    let tx = Transaction::new_signed_tx(&light_priv_key, vec![], 0);
+    assert!(accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .is_err());
+
+    let tx = Transaction::new_signed_tx(&heavy_priv_key, vec![], 0);
+    let addr = accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+    assert_eq!(addr, root_addr);
+
+    // Removing the heavy key would leave the account without a key reaching the threshold,
+    // although the remaining keys together do.
+    assert!(accounts
+        .call(
+            call::CallMessage::RemoveKey {
+                pub_key: heavy_priv_key.pub_key(),
+            },
+            &context,
+            native_working_set,
+        )
+        .is_err());
+
+    accounts
+        .call(
+            call::CallMessage::RemoveKey {
+                pub_key: light_priv_key.pub_key(),
+            },
+            &context,
+            native_working_set,
+        )
+        .unwrap();
+
+    // A removed key is an unknown key, so it gets its own default account.
+    let tx = Transaction::new_signed_tx(&light_priv_key, vec![], 0);
+    let addr = accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+    assert_ne!(addr, root_addr);
+}
+
+#[test]
+fn test_key_rotation_timelock() {
+    const DELAY: u64 = 2;
+    let native_working_set = &mut WorkingSet::new(ProverStorage::temporary());
+    let accounts = &mut Accounts::<C>::new();
+
+    let root_priv_key = DefaultPrivateKey::generate();
+    let root_addr = root_priv_key.pub_key().to_address::<<C as Spec>::Address>();
+    let context = C::new(root_addr.clone());
+    let account_config = AccountConfig::<C> {
+        pub_keys: vec![root_priv_key.pub_key()],
+        key_rotation_delay: DELAY,
+        chain_id: 0,
+    };
+    accounts
+        .genesis(&account_config, native_working_set)
+        .unwrap();
+
+    let new_priv_key = DefaultPrivateKey::generate();
+    accounts
+        .call(
+            call::CallMessage::ScheduleKeyRotation {
+                old_pub_key: root_priv_key.pub_key(),
+                new_pub_key: new_priv_key.pub_key(),
+                signature: sign_update_account_msg(
+                    accounts,
+                    &new_priv_key,
+                    &root_addr,
+                    native_working_set,
+                ),
+            },
+            &context,
+            native_working_set,
+        )
+        .unwrap();
+
+    for nonce in 0..DELAY {
+        // The old key is still valid until the delay has passed.
+        let tx = Transaction::new_signed_tx(&root_priv_key, vec![], nonce);
+        let addr = accounts
+            .pre_dispatch_tx_hook(tx, native_working_set)
+            .unwrap();
+        assert_eq!(addr, root_addr);
+
+        accounts
+            .begin_blob_hook(&[], &[], native_working_set)
+            .unwrap();
+    }
+
+    let tx = Transaction::new_signed_tx(&new_priv_key, vec![], DELAY);
+    let addr = accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+    assert_eq!(addr, root_addr);
+
+    // The old key is no longer associated with the account.
+    assert_eq!(
+        accounts.get_account(root_priv_key.pub_key(), native_working_set),
+        query::Response::AccountEmpty
+    );
+}
+
+#[test]
+fn test_session_key() {
+    const ALLOWED_PREFIX: u8 = 3;
+    let native_working_set = &mut WorkingSet::new(ProverStorage::temporary());
+    let accounts = &mut Accounts::<C>::new();
+
+    let root_pub_key = DefaultPrivateKey::generate().pub_key();
+    let root_addr = root_pub_key.to_address::<<C as Spec>::Address>();
+    let context = C::new(root_addr.clone());
+    let account_config = AccountConfig::<C> {
+        pub_keys: vec![root_pub_key],
+        key_rotation_delay: 0,
+        chain_id: 0,
+    };
+    accounts
+        .genesis(&account_config, native_working_set)
+        .unwrap();
+
+    let session_priv_key = DefaultPrivateKey::generate();
+
+    accounts
+        .call(
+            call::CallMessage::AddSessionKey {
+                pub_key: session_priv_key.pub_key(),
+                allowed_message_prefixes: vec![vec![ALLOWED_PREFIX]],
+                expires_at: 1,
+                signature: sign_update_account_msg(
+                    accounts,
+                    &session_priv_key,
+                    &root_addr,
+                    native_working_set,
+                ),
+            },
+            &context,
+            native_working_set,
+        )
+        .unwrap();
+
+    let tx = Transaction::new_signed_tx(&session_priv_key, vec![ALLOWED_PREFIX + 1, 0], 0);
+    assert!(accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .is_err());
+
+    let tx = Transaction::new_signed_tx(&session_priv_key, vec![ALLOWED_PREFIX, 0], 0);
+    let addr = accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+    assert_eq!(addr, root_addr);
+
+    // A session key can't call the accounts module, which would let it manage the keys of the account.
+    let tx = Transaction::new_signed_tx(&session_priv_key, vec![ALLOWED_PREFIX, 0], 1);
+    assert!(accounts
+        .pre_dispatch_call_hook(&tx, &accounts.address, native_working_set)
+        .is_err());
+    accounts
+        .pre_dispatch_call_hook(&tx, &root_addr, native_working_set)
+        .unwrap();
+    let tx = Transaction::new_signed_tx(&DefaultPrivateKey::generate(), vec![], 0);
+    accounts
+        .pre_dispatch_call_hook(&tx, &accounts.address, native_working_set)
+        .unwrap();
+
+    // The session key expires after batch height 1.
+    for _ in 0..2 {
+        accounts
+            .begin_blob_hook(&[], &[], native_working_set)
+            .unwrap();
+    }
+
+    let tx = Transaction::new_signed_tx(&session_priv_key, vec![ALLOWED_PREFIX, 0], 1);
+    assert!(accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .is_err());
+}
+
+#[test]
+fn test_nonce_lanes() {
+    let native_working_set = &mut WorkingSet::new(ProverStorage::temporary());
+    let accounts = &mut Accounts::<C>::new();
+
+    let priv_key = DefaultPrivateKey::generate();
+    let addr = priv_key.pub_key().to_address::<<C as Spec>::Address>();
+
+    let tx = Transaction::new_signed_tx(&priv_key, vec![], 0);
+    accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+
+    // A transaction from lane 1 is dropped, the lane stalls...
+    let tx = Transaction::new_signed_tx_in_lane(&priv_key, vec![], 1, 1);
+    assert!(accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .is_err());
+
+    // ...but the other lanes don't.
+    let tx = Transaction::new_signed_tx(&priv_key, vec![], 1);
+    accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+
+    let tx = Transaction::new_signed_tx_in_lane(&priv_key, vec![], 2, 0);
+    accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .unwrap();
+
+    assert_eq!(
+        accounts.get_lane_nonce(addr.clone(), 0, native_working_set),
+        Some(2)
+    );
+    assert_eq!(
+        accounts.get_lane_nonce(addr.clone(), 1, native_working_set),
+        Some(0)
+    );
+    assert_eq!(
+        accounts.get_lane_nonce(addr, 2, native_working_set),
+        Some(1)
+    );
+
+    // The number of lanes of an account is bounded, lane 1 doesn't count since it was never used.
+    for nonce_lane in 3..=crate::MAX_NONCE_LANES + 1 {
+        let tx = Transaction::new_signed_tx_in_lane(&priv_key, vec![], nonce_lane, 0);
+        accounts
+            .pre_dispatch_tx_hook(tx, native_working_set)
+            .unwrap();
+    }
+    let tx = Transaction::new_signed_tx_in_lane(&priv_key, vec![], crate::MAX_NONCE_LANES + 2, 0);
+    assert!(accounts
+        .pre_dispatch_tx_hook(tx, native_working_set)
+        .is_err());
+}
+
+fn add_key(
+    accounts: &Accounts<C>,
+    priv_key: &DefaultPrivateKey,
+    weight: u64,
+    context: &C,
+    working_set: &mut WorkingSet<<C as Spec>::Storage>,
+) {
+    let signature = sign_update_account_msg(accounts, priv_key, context.sender(), working_set);
+    accounts
+        .call(
+            call::CallMessage::AddKey {
+                pub_key: priv_key.pub_key(),
+                weight,
+                signature,
+            },
+            context,
+            working_set,
+        )
+        .unwrap();
+}
+
+fn sign_update_account_msg(
+    accounts: &Accounts<C>,
+    priv_key: &DefaultPrivateKey,
+    addr: &<C as Spec>::Address,
+    working_set: &mut WorkingSet<<C as Spec>::Storage>,
+) -> <C as Spec>::Signature {
+    let (_, account) = accounts.root_account(addr, working_set).unwrap();
+    let chain_id = accounts.chain_id.get(working_set).unwrap_or_default();
+    priv_key.sign(call::update_account_msg::<C>(
+        addr,
+        0,
+        account.nonce,
+        chain_id,
+    ))
+}
+
 #[test]
 fn test_response_serialization() {
     let addr: Vec<u8> = (1..=32).collect();
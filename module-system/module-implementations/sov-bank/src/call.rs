@@ -152,6 +152,28 @@ impl<C: sov_modules_api::Context> Bank<C> {
         token.transfer(from, to, coins.amount, working_set)?;
         Ok(CallResponse::default())
     }
+
+    /// Returns the address of the native token of the rollup, set at genesis.
+    pub fn get_native_token_address(
+        &self,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<C::Address> {
+        self.native_token_address
+            .get(working_set)
+            .ok_or_else(|| anyhow::anyhow!("Native token is not set"))
+    }
+
+    /// Builds `Coins` of the native token for the given `amount`.
+    pub fn native_coins(
+        &self,
+        amount: Amount,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<Coins<C>> {
+        Ok(Coins {
+            amount,
+            token_address: self.get_native_token_address(working_set)?,
+        })
+    }
 }
 
 pub(crate) fn prefix_from_address_with_parent<C: sov_modules_api::Context>(
@@ -1,4 +1,4 @@
-use crate::{token::Token, Bank};
+use crate::{token::Token, Bank, TokenConfig};
 use anyhow::{bail, Result};
 use sov_state::WorkingSet;
 
@@ -11,24 +11,38 @@ impl<C: sov_modules_api::Context> Bank<C> {
         config: &<Self as sov_modules_api::Module>::Config,
         working_set: &mut WorkingSet<C::Storage>,
     ) -> Result<()> {
-        let parent_prefix = self.tokens.prefix();
+        if let Some(native_token) = &config.native_token {
+            let native_token_address = self.create_genesis_token(native_token, working_set)?;
+            self.native_token_address
+                .set(&native_token_address, working_set);
+        }
+
         for token_config in config.tokens.iter() {
-            let (token_address, token) = Token::<C>::create(
-                &token_config.token_name,
-                &token_config.address_and_balances,
-                vec![C::Address::try_from(&DEPLOYER)?],
-                &DEPLOYER,
-                SALT,
-                parent_prefix,
-                working_set,
-            )?;
+            self.create_genesis_token(token_config, working_set)?;
+        }
+        Ok(())
+    }
 
-            if self.tokens.get(&token_address, working_set).is_some() {
-                bail!("Token address already exists");
-            }
+    fn create_genesis_token(
+        &self,
+        token_config: &TokenConfig<C>,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<C::Address> {
+        let (token_address, token) = Token::<C>::create(
+            &token_config.token_name,
+            &token_config.address_and_balances,
+            vec![C::Address::try_from(&DEPLOYER)?],
+            &DEPLOYER,
+            SALT,
+            self.tokens.prefix(),
+            working_set,
+        )?;
 
-            self.tokens.set(&token_address, &token, working_set);
+        if self.tokens.get(&token_address, working_set).is_some() {
+            bail!("Token address already exists");
         }
-        Ok(())
+
+        self.tokens.set(&token_address, &token, working_set);
+        Ok(token_address)
     }
 }
@@ -20,6 +20,8 @@ pub struct TokenConfig<C: sov_modules_api::Context> {
 
 /// Initial configuration for sov-bank module.
 pub struct BankConfig<C: sov_modules_api::Context> {
+    /// The native token of the rollup. Fees, sequencer bonds and prover bonds are denominated in it.
+    pub native_token: Option<TokenConfig<C>>,
     pub tokens: Vec<TokenConfig<C>>,
 }
 
@@ -36,6 +38,10 @@ pub struct Bank<C: sov_modules_api::Context> {
     /// A mapping of addresses to tokens in the sov-bank.
     #[state]
     pub(crate) tokens: sov_state::StateMap<C::Address, Token<C>>,
+
+    /// The address of the native token of the rollup.
+    #[state]
+    pub(crate) native_token_address: sov_state::StateValue<C::Address>,
 }
 
 impl<C: sov_modules_api::Context> sov_modules_api::Module for Bank<C> {
@@ -32,6 +32,8 @@ pub(crate) struct Token<C: sov_modules_api::Context> {
     /// Non empty vector indicates members of the vector can mint.
     /// Freezing a token requires emptying the vector
     /// NOTE: This is explicit so if a creator doesn't add themselves, then they can't mint
+    /// The minting rights are specific to each token, so this vector is their only record,
+    /// they are not granted as `sov_modules_api::access` roles.
     pub(crate) authorized_minters: Vec<C::Address>,
 }
 
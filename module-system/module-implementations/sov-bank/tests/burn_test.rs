@@ -16,7 +16,10 @@ pub type Storage = ProverStorage<DefaultStorageSpec>;
 fn burn_deployed_tokens() {
     let bank = Bank::<C>::new();
     let mut working_set = WorkingSet::new(ProverStorage::temporary());
-    let empty_bank_config = BankConfig::<C> { tokens: vec![] };
+    let empty_bank_config = BankConfig::<C> {
+        native_token: None,
+        tokens: vec![],
+    };
     bank.genesis(&empty_bank_config, &mut working_set).unwrap();
 
     let sender_address = generate_address("just_sender");
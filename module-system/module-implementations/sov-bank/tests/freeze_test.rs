@@ -13,7 +13,10 @@ pub type Storage = ProverStorage<DefaultStorageSpec>;
 fn freeze_token() {
     let bank = Bank::<C>::new();
     let mut working_set = WorkingSet::new(ProverStorage::temporary());
-    let empty_bank_config = BankConfig::<C> { tokens: vec![] };
+    let empty_bank_config = BankConfig::<C> {
+        native_token: None,
+        tokens: vec![],
+    };
     bank.genesis(&empty_bank_config, &mut working_set).unwrap();
 
     let minter_address = generate_address("minter");
@@ -28,6 +28,7 @@ pub fn create_bank_config_with_token(
     };
 
     BankConfig {
+        native_token: None,
         tokens: vec![token_config],
     }
 }
@@ -0,0 +1,46 @@
+mod helpers;
+
+use helpers::*;
+use sov_bank::genesis::{DEPLOYER, SALT};
+use sov_bank::{create_token_address, Bank, BankConfig, TokenConfig};
+use sov_modules_api::{Module, ModuleInfo};
+use sov_state::{ProverStorage, WorkingSet};
+
+#[test]
+fn native_token_set_at_genesis() {
+    let initial_balance = 100;
+    let holder_address = generate_address("holder");
+    let native_token = TokenConfig {
+        token_name: "NativeToken".to_owned(),
+        address_and_balances: vec![(holder_address.clone(), initial_balance)],
+    };
+    let bank_config = BankConfig::<C> {
+        native_token: Some(native_token),
+        tokens: vec![],
+    };
+
+    let mut working_set = WorkingSet::new(ProverStorage::temporary());
+    let bank = Bank::new();
+    bank.genesis(&bank_config, &mut working_set).unwrap();
+
+    let expected_address = create_token_address::<C>("NativeToken", &DEPLOYER, SALT);
+    let native_token_address = bank.get_native_token_address(&mut working_set).unwrap();
+    assert_eq!(expected_address, native_token_address);
+
+    let balance = bank.get_balance_of(holder_address, native_token_address, &mut working_set);
+    assert_eq!(Some(initial_balance), balance);
+
+    let coins = bank.native_coins(10, &mut working_set).unwrap();
+    assert_eq!(expected_address, coins.token_address);
+}
+
+#[test]
+fn native_token_missing() {
+    let bank_config = create_bank_config_with_token(1, 100);
+    let mut working_set = WorkingSet::new(ProverStorage::temporary());
+    let bank = Bank::new();
+    bank.genesis(&bank_config, &mut working_set).unwrap();
+
+    assert!(bank.get_native_token_address(&mut working_set).is_err());
+    assert!(bank.native_coins(10, &mut working_set).is_err());
+}
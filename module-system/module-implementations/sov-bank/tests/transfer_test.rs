@@ -201,7 +201,10 @@ fn transfer_initial_token() {
 fn transfer_deployed_token() {
     let bank = Bank::<C>::new();
     let mut working_set = WorkingSet::new(ProverStorage::temporary());
-    let empty_bank_config = BankConfig::<C> { tokens: vec![] };
+    let empty_bank_config = BankConfig::<C> {
+        native_token: None,
+        tokens: vec![],
+    };
     bank.genesis(&empty_bank_config, &mut working_set).unwrap();
 
     let sender_address = generate_address("just_sender");
@@ -1,7 +1,6 @@
 use anyhow::Result;
 use borsh::{BorshDeserialize, BorshSerialize};
-use sov_bank::Coins;
-use sov_modules_api::CallResponse;
+use sov_modules_api::{call_module, CallResponse};
 use sov_rollup_interface::zk::traits::Zkvm;
 use sov_state::WorkingSet;
 use std::fmt::Debug;
@@ -29,13 +28,7 @@ impl<C: sov_modules_api::Context, Vm: Zkvm> ProverIncentives<C, Vm> {
     ) -> Result<CallResponse> {
         // Transfer the bond amount from the sender to the module's address.
         // On failure, no state is changed
-        let coins = Coins {
-            token_address: self
-                .bonding_token_address
-                .get(working_set)
-                .expect("Bonding token address must be set"),
-            amount: bond_amount,
-        };
+        let coins = self.bank.native_coins(bond_amount, working_set)?;
         self.bank
             .transfer_from(prover, &self.address, coins, working_set)?;
 
@@ -77,17 +70,18 @@ impl<C: sov_modules_api::Context, Vm: Zkvm> ProverIncentives<C, Vm> {
         if let Some(old_balance) = self.bonded_provers.get(context.sender(), working_set) {
             // Transfer the bond amount from the sender to the module's address.
             // On failure, no state is changed
-            let coins = Coins {
-                token_address: self
-                    .bonding_token_address
-                    .get(working_set)
-                    .expect("Bonding token address must be set"),
-                amount: old_balance,
-            };
-            // Try to unbond the entire balance
+            let coins = self.bank.native_coins(old_balance, working_set)?;
+            // Try to unbond the entire balance, the bank is called with the module's address as sender
             // If the unbonding fails, no state is changed
-            self.bank
-                .transfer_from(&self.address, context.sender(), coins, working_set)?;
+            call_module(
+                self,
+                &self.bank,
+                sov_bank::call::CallMessage::Transfer {
+                    to: context.sender().clone(),
+                    coins,
+                },
+                working_set,
+            )?;
 
             // Update our internal tracking of the total bonded amount for the sender.
             self.bonded_provers.set(context.sender(), &0, working_set);
@@ -22,8 +22,6 @@ impl<C: sov_modules_api::Context, Vm: Zkvm> ProverIncentives<C, Vm> {
             },
             working_set,
         );
-        self.bonding_token_address
-            .set(&config.bonding_token_address, working_set);
 
         for (prover, bond) in config.initial_provers.iter() {
             self.bond_prover_helper(*bond, prover, working_set)?;
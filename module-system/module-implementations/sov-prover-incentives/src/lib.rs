@@ -14,8 +14,6 @@ use sov_rollup_interface::zk::traits::Zkvm;
 use sov_state::WorkingSet;
 
 pub struct ProverIncentivesConfig<C: Context, Vm: Zkvm> {
-    /// The address of the token to be used for bonding.
-    bonding_token_address: C::Address,
     /// The minimum bond for a prover.
     minimum_bond: u64,
     /// A code commitment to be used for verifying proofs
@@ -55,10 +53,6 @@ pub struct ProverIncentives<C: sov_modules_api::Context, Vm: Zkvm> {
     #[address]
     pub address: C::Address,
 
-    /// The address of the token used for bonding provers
-    #[state]
-    pub bonding_token_address: sov_state::StateValue<C::Address>,
-
     /// The code commitment to be used for verifying proofs
     #[state]
     pub commitment_of_allowed_verifier_method: sov_state::StateValue<StoredCodeCommitment<Vm>>,
@@ -24,7 +24,8 @@ fn create_bank_config() -> (sov_bank::BankConfig<C>, <C as Spec>::Address) {
 
     (
         sov_bank::BankConfig {
-            tokens: vec![token_config],
+            native_token: Some(token_config),
+            tokens: vec![],
         },
         prover_address,
     )
@@ -39,16 +40,9 @@ fn setup(
     bank.genesis(&bank_config, working_set)
         .expect("bank genesis must succeed");
 
-    let token_address = sov_bank::create_token_address::<C>(
-        &bank_config.tokens[0].token_name,
-        &sov_bank::genesis::DEPLOYER,
-        sov_bank::genesis::SALT,
-    );
-
     // initialize prover incentives
     let module = ProverIncentives::<C, MockZkvm>::new();
     let config = crate::ProverIncentivesConfig {
-        bonding_token_address: token_address,
         minimum_bond: BOND_AMOUNT,
         commitment_of_allowed_verifier_method: MockCodeCommitment([0u8; 32]),
         initial_provers: vec![(prover_address.clone(), BOND_AMOUNT)],
@@ -145,9 +139,9 @@ fn test_unbonding() {
         sender: prover_address.clone(),
     };
     let token_address = module
-        .bonding_token_address
-        .get(&mut working_set)
-        .expect("bonding token address was set at genesis");
+        .bank
+        .get_native_token_address(&mut working_set)
+        .expect("native token address was set at genesis");
 
     // Assert that the prover has bonded tokens
     assert_eq!(
@@ -13,7 +13,7 @@ impl<C: sov_modules_api::Context> Sequencer<C> {
 
         self.seq_da_address.set(&config.seq_da_address, working_set);
 
-        self.coins_to_lock.set(&config.coins_to_lock, working_set);
+        self.amount_to_lock.set(&config.amount_to_lock, working_set);
 
         Ok(())
     }
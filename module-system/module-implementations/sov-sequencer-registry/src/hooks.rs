@@ -25,7 +25,7 @@ impl<C: Context> ApplyBlobHooks for Sequencer<C> {
 
         let sequencer = &self.seq_rollup_address.get_or_err(working_set)?;
         let locker = &self.address;
-        let coins = self.coins_to_lock.get_or_err(working_set)?;
+        let coins = self.coins_to_lock(working_set)?;
 
         self.bank
             .transfer_from(sequencer, locker, coins, working_set)?;
@@ -40,7 +40,7 @@ impl<C: Context> ApplyBlobHooks for Sequencer<C> {
     ) -> anyhow::Result<()> {
         let sequencer = &self.seq_rollup_address.get_or_err(working_set)?;
         let locker = &self.address;
-        let coins = self.coins_to_lock.get_or_err(working_set)?;
+        let coins = self.coins_to_lock(working_set)?;
 
         self.bank
             .transfer_from(locker, sequencer, coins, working_set)?;
@@ -48,3 +48,14 @@ impl<C: Context> ApplyBlobHooks for Sequencer<C> {
         Ok(())
     }
 }
+
+impl<C: Context> Sequencer<C> {
+    /// Coins of the native token locked while a blob of the sequencer is processed.
+    fn coins_to_lock(
+        &self,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> anyhow::Result<sov_bank::Coins<C>> {
+        let amount = self.amount_to_lock.get_or_err(working_set)?;
+        self.bank.native_coins(amount, working_set)
+    }
+}
@@ -11,7 +11,8 @@ use sov_state::{StateValue, WorkingSet};
 pub struct SequencerConfig<C: sov_modules_api::Context> {
     pub seq_rollup_address: C::Address,
     pub seq_da_address: Vec<u8>,
-    pub coins_to_lock: sov_bank::Coins<C>,
+    /// The amount of the native token locked while a blob of the sequencer is processed.
+    pub amount_to_lock: u64,
 }
 
 #[derive(ModuleInfo)]
@@ -33,11 +34,11 @@ pub struct Sequencer<C: sov_modules_api::Context> {
     #[state]
     pub(crate) seq_da_address: StateValue<Vec<u8>>,
 
-    /// Coin's that will be slashed if the sequencer is malicious.
+    /// Amount of the native token that will be slashed if the sequencer is malicious.
     /// The coins will be transferred from `self.seq_rollup_address` to `self.address`
     /// and locked forever.
     #[state]
-    pub(crate) coins_to_lock: StateValue<sov_bank::Coins<C>>,
+    pub(crate) amount_to_lock: StateValue<u64>,
 }
 
 impl<C: sov_modules_api::Context> sov_modules_api::Module for Sequencer<C> {
@@ -36,10 +36,10 @@ impl<C: Context> Sequencer<C> {
 impl<C: Context> Sequencer<C> {
     fn get_seq_and_balance(&self, working_set: &mut WorkingSet<C::Storage>) -> Option<Data> {
         let seq_address = self.seq_rollup_address.get(working_set)?;
-        let coins = self.coins_to_lock.get(working_set)?;
-        let balance =
-            self.bank
-                .get_balance_of(seq_address.clone(), coins.token_address, working_set)?;
+        let token_address = self.bank.get_native_token_address(working_set).ok()?;
+        let balance = self
+            .bank
+            .get_balance_of(seq_address.clone(), token_address, working_set)?;
 
         Some(Data {
             address: seq_address.into(),
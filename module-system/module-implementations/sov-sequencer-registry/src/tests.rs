@@ -40,9 +40,10 @@ impl TestSequencer {
         &mut self,
         working_set: &mut WorkingSet<<C as Spec>::Storage>,
     ) -> sov_bank::query::BalanceResponse {
+        let token_address = self.bank.get_native_token_address(working_set).unwrap();
         self.bank.balance_of(
             self.sequencer_config.seq_rollup_address.clone(),
-            self.sequencer_config.coins_to_lock.token_address.clone(),
+            token_address,
             working_set,
         )
     }
@@ -58,23 +59,18 @@ fn create_bank_config() -> (sov_bank::BankConfig<C>, <C as Spec>::Address) {
 
     (
         sov_bank::BankConfig {
-            tokens: vec![token_config],
+            native_token: Some(token_config),
+            tokens: vec![],
         },
         seq_address,
     )
 }
 
-fn create_sequencer_config(
-    seq_rollup_address: <C as Spec>::Address,
-    token_address: <C as Spec>::Address,
-) -> SequencerConfig<C> {
+fn create_sequencer_config(seq_rollup_address: <C as Spec>::Address) -> SequencerConfig<C> {
     SequencerConfig {
         seq_rollup_address,
         seq_da_address: SEQUENCER_DA_ADDRESS.to_vec(),
-        coins_to_lock: sov_bank::Coins {
-            amount: LOCKED_AMOUNT,
-            token_address,
-        },
+        amount_to_lock: LOCKED_AMOUNT,
     }
 }
 
@@ -82,14 +78,8 @@ fn create_test_sequencer() -> TestSequencer {
     let bank = sov_bank::Bank::<C>::new();
     let (bank_config, seq_rollup_address) = create_bank_config();
 
-    let token_address = sov_bank::create_token_address::<C>(
-        &bank_config.tokens[0].token_name,
-        &sov_bank::genesis::DEPLOYER,
-        sov_bank::genesis::SALT,
-    );
-
     let sequencer = Sequencer::<C>::new();
-    let sequencer_config = create_sequencer_config(seq_rollup_address, token_address);
+    let sequencer_config = create_sequencer_config(seq_rollup_address);
 
     TestSequencer {
         bank,
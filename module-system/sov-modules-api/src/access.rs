@@ -0,0 +1,92 @@
+//! Role based access control for the call messages of modules.
+//!
+//! A module stores the roles granted to addresses in a [`Roles`] state variable, and its call message
+//! declares the role required by each message with [`AccessControl`] (usually derived with
+//! `#[derive(AccessControl)]` and `#[access(role = "...")]` attributes). The module implements
+//! [`Module::check_access`](crate::Module::check_access) with [`Roles::check_access`], which the derived
+//! `DispatchCall` of the runtime runs before [`Module::call`](crate::Module::call). The module can be called
+//! without the runtime, so its handlers still check the role of the sender with [`Roles::ensure_role`].
+use crate::{Context, Spec};
+use sov_state::{Prefix, StateMap, WorkingSet};
+
+/// Implemented by call messages which can only be sent by the addresses having a role.
+pub trait AccessControl {
+    /// Returns the role required to send the message, `None` if anyone can send it.
+    fn required_role(&self) -> Option<&'static str>;
+}
+
+/// The roles granted to addresses, stored in the state of a module.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Roles<C: Context> {
+    grants: StateMap<(String, C::Address), bool>,
+}
+
+impl<C: Context> Roles<C> {
+    pub fn new(prefix: Prefix) -> Self {
+        Self {
+            grants: StateMap::new(prefix),
+        }
+    }
+
+    /// Gives the role to the address.
+    pub fn grant(
+        &self,
+        role: &str,
+        address: &C::Address,
+        working_set: &mut WorkingSet<<C as Spec>::Storage>,
+    ) {
+        self.grants
+            .set(&(role.to_owned(), address.clone()), &true, working_set);
+    }
+
+    /// Takes the role away from the address.
+    pub fn revoke(
+        &self,
+        role: &str,
+        address: &C::Address,
+        working_set: &mut WorkingSet<<C as Spec>::Storage>,
+    ) {
+        self.grants
+            .delete(&(role.to_owned(), address.clone()), working_set);
+    }
+
+    pub fn has_role(
+        &self,
+        role: &str,
+        address: &C::Address,
+        working_set: &mut WorkingSet<<C as Spec>::Storage>,
+    ) -> bool {
+        self.grants
+            .get(&(role.to_owned(), address.clone()), working_set)
+            .unwrap_or_default()
+    }
+
+    /// Fails if the address doesn't have the role.
+    pub fn ensure_role(
+        &self,
+        role: &str,
+        address: &C::Address,
+        working_set: &mut WorkingSet<<C as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.has_role(role, address, working_set),
+            "Address {} doesn't have the role `{}`",
+            address,
+            role
+        );
+        Ok(())
+    }
+
+    /// Fails if the sender doesn't have the role required by the message.
+    pub fn check_access<M: AccessControl>(
+        &self,
+        message: &M,
+        context: &C,
+        working_set: &mut WorkingSet<<C as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
+        match message.required_role() {
+            Some(role) => self.ensure_role(role, context.sender(), working_set),
+            None => Ok(()),
+        }
+    }
+}
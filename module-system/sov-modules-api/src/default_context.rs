@@ -5,14 +5,15 @@ use jmt::SimpleHasher;
 #[cfg(feature = "native")]
 use serde::{Deserialize, Serialize};
 use sov_state::DefaultStorageSpec;
-#[cfg(feature = "native")]
-use sov_state::ProverStorage;
 use sov_state::{ArrayWitness, ZkStorage};
+#[cfg(feature = "native")]
+use sov_state::{InMemoryStorage, ProverStorage};
 
 #[cfg(feature = "native")]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DefaultContext {
     pub sender: Address,
+    pub nonce_lane: u64,
 }
 
 #[cfg(feature = "native")]
@@ -32,13 +33,66 @@ impl Context for DefaultContext {
     }
 
     fn new(sender: Self::Address) -> Self {
-        Self { sender }
+        Self {
+            sender,
+            nonce_lane: 0,
+        }
+    }
+
+    fn nonce_lane(&self) -> u64 {
+        self.nonce_lane
+    }
+
+    fn with_nonce_lane(self, nonce_lane: u64) -> Self {
+        Self { nonce_lane, ..self }
+    }
+}
+
+/// Same as [`DefaultContext`], with a storage which is kept in memory. Produces the same state roots and witnesses,
+/// so it can replace the `DefaultContext` in tests which don't need to persist the state.
+#[cfg(feature = "native")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InMemoryContext {
+    pub sender: Address,
+    pub nonce_lane: u64,
+}
+
+#[cfg(feature = "native")]
+impl Spec for InMemoryContext {
+    type Address = Address;
+    type Storage = InMemoryStorage<DefaultStorageSpec>;
+    type PublicKey = DefaultPublicKey;
+    type Hasher = sha2::Sha256;
+    type Signature = DefaultSignature;
+    type Witness = ArrayWitness;
+}
+
+#[cfg(feature = "native")]
+impl Context for InMemoryContext {
+    fn sender(&self) -> &Self::Address {
+        &self.sender
+    }
+
+    fn new(sender: Self::Address) -> Self {
+        Self {
+            sender,
+            nonce_lane: 0,
+        }
+    }
+
+    fn nonce_lane(&self) -> u64 {
+        self.nonce_lane
+    }
+
+    fn with_nonce_lane(self, nonce_lane: u64) -> Self {
+        Self { nonce_lane, ..self }
     }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct ZkDefaultContext {
     pub sender: Address,
+    pub nonce_lane: u64,
 }
 
 impl Spec for ZkDefaultContext {
@@ -56,7 +110,18 @@ impl Context for ZkDefaultContext {
     }
 
     fn new(sender: Self::Address) -> Self {
-        Self { sender }
+        Self {
+            sender,
+            nonce_lane: 0,
+        }
+    }
+
+    fn nonce_lane(&self) -> u64 {
+        self.nonce_lane
+    }
+
+    fn with_nonce_lane(self, nonce_lane: u64) -> Self {
+        Self { nonce_lane, ..self }
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::{CallResponse, Context, Error, Spec};
+use crate::{CallResponse, Context, Error, Module, ModuleInfo, Spec};
 use sov_state::WorkingSet;
 
 /// Methods from this trait should be called only once during the rollup deployment.
@@ -35,3 +35,27 @@ pub trait DispatchCall {
     /// Returns an address of the dispatched module.
     fn module_address(&self, message: &Self::Decodable) -> &<Self::Context as Spec>::Address;
 }
+
+/// Calls `callee` with `message` on behalf of the `caller` module: the message is sent with the address of `caller`
+/// as sender, and goes through the [`Module::check_access`] of `callee` like a message sent in a transaction.
+///
+/// The events of the call are attributed to the address of `callee`. If the call fails, its writes and events are
+/// reverted and the error is returned to `caller`, which can either propagate it or carry on.
+pub fn call_module<C, Caller, Callee>(
+    caller: &Caller,
+    callee: &Callee,
+    message: <Callee as Module>::CallMessage,
+    working_set: &mut WorkingSet<C::Storage>,
+) -> Result<CallResponse, Error>
+where
+    C: Context,
+    Caller: ModuleInfo<Context = C>,
+    Callee: Module<Context = C> + ModuleInfo<Context = C>,
+{
+    let context = C::new(caller.address().clone());
+    let emitter = callee.address().to_string();
+    working_set.execute_nested(&emitter, |working_set| {
+        callee.check_access(&message, &context, working_set)?;
+        callee.call(message, &context, working_set)
+    })
+}
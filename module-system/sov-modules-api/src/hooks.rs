@@ -12,6 +12,17 @@ pub trait TxHooks {
         working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
     ) -> anyhow::Result<<Self::Context as Spec>::Address>;
 
+    /// Runs once the message of the transaction is decoded, just before it is dispatched to the module
+    /// at `module_address`. The transaction is reverted if it fails.
+    fn pre_dispatch_call_hook(
+        &self,
+        _tx: &Transaction<Self::Context>,
+        _module_address: &<Self::Context as Spec>::Address,
+        _working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     /// Runs after the tx is dispatched to an appropriate module.
     fn post_dispatch_tx_hook(
         &self,
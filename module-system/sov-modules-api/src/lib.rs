@@ -1,5 +1,6 @@
 #![feature(associated_type_defaults)]
 
+pub mod access;
 mod bech32;
 pub mod default_context;
 pub mod default_signature;
@@ -7,6 +8,7 @@ mod dispatch;
 mod encode;
 mod error;
 pub mod hooks;
+pub mod migration;
 mod prefix;
 mod response;
 mod serde_address;
@@ -16,10 +18,10 @@ pub mod transaction;
 pub use crate::bech32::AddressBech32;
 use borsh::{BorshDeserialize, BorshSerialize};
 use core::fmt::{self, Debug, Display};
-pub use dispatch::{DispatchCall, Genesis};
+pub use dispatch::{call_module, DispatchCall, Genesis};
 pub use error::Error;
 pub use jmt::SimpleHasher as Hasher;
-pub use prefix::Prefix;
+pub use prefix::{check_prefix_collisions, Prefix};
 pub use response::CallResponse;
 use serde::{Deserialize, Serialize};
 pub use sov_rollup_interface::traits::AddressTrait;
@@ -149,7 +151,7 @@ pub trait Spec {
 
 /// A context contains information which is passed to modules during
 /// transaction execution. Currently, context includes the sender of the transaction
-/// as recovered from its signature.
+/// as recovered from its signature, and the nonce lane of the transaction.
 ///
 /// Context objects also implement the [`Spec`] trait, which specifies the types to be used in this
 /// instance of the state transition function. By making modules generic over a `Context`, developers
@@ -160,11 +162,17 @@ pub trait Context: Spec + Clone + Debug + PartialEq {
 
     /// Constructor for the Context.
     fn new(sender: Self::Address) -> Self;
+
+    /// Nonce lane of the transaction, `0` for the main lane (see [`transaction::Transaction::nonce_lane`]).
+    fn nonce_lane(&self) -> u64;
+
+    /// Returns the context of a transaction sent in `nonce_lane`.
+    fn with_nonce_lane(self, nonce_lane: u64) -> Self;
 }
 
 impl<T> Genesis for T
 where
-    T: Module,
+    T: Module + ModuleInfo<Context = <T as Module>::Context>,
 {
     type Context = <Self as Module>::Context;
 
@@ -175,7 +183,12 @@ where
         config: &Self::Config,
         working_set: &mut WorkingSet<<<Self as Genesis>::Context as Spec>::Storage>,
     ) -> Result<(), Error> {
-        <Self as Module>::genesis(self, config, working_set)
+        <Self as Module>::genesis(self, config, working_set)?;
+        // A module deployed at genesis starts with the latest state layout.
+        if <Self as Module>::SCHEMA_VERSION != 0 {
+            migration::set_schema_version(self, <Self as Module>::SCHEMA_VERSION, working_set);
+        }
+        Ok(())
     }
 }
 
@@ -192,6 +205,10 @@ pub trait Module {
     /// Module defined argument to the call method.
     type CallMessage: Debug + BorshSerialize + BorshDeserialize = NonInstantiable;
 
+    /// Version of the module state layout. Increase it, and implement [`Module::migrate`],
+    /// when an upgrade of the module changes its storage layout or needs to rewrite its state.
+    const SCHEMA_VERSION: u64 = 0;
+
     /// Genesis is called when a rollup is deployed and can be used to set initial state values in the module.
     fn genesis(
         &self,
@@ -211,6 +228,27 @@ pub trait Module {
     ) -> Result<CallResponse, Error> {
         unreachable!()
     }
+
+    /// Checks that the sender is allowed to send the message, the derived `DispatchCall` of the runtime
+    /// runs it before [`Module::call`]. See [`access`] for role based access control.
+    fn check_access(
+        &self,
+        _message: &Self::CallMessage,
+        _context: &Self::Context,
+        _working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Migrates the module state from `from_version` to [`Module::SCHEMA_VERSION`].
+    /// It is run once, by [`migration::migrate_module`], when the rollup reaches the activation height of the upgrade.
+    fn migrate(
+        &self,
+        _from_version: u64,
+        _working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 /// Every module has to implement this trait.
@@ -222,6 +260,13 @@ pub trait ModuleInfo {
 
     /// Returns address of the module.
     fn address(&self) -> &<Self::Context as Spec>::Address;
+
+    /// Returns the prefixes of the state variables of the module, including the ones of its nested modules.
+    /// They are only used to detect the modules whose state overlaps, so a hand-written implementation
+    /// which doesn't list them is never reported.
+    fn prefixes() -> Vec<Prefix> {
+        Vec::new()
+    }
 }
 
 /// A StateTransitionRunner needs to implement this if
@@ -0,0 +1,122 @@
+//! Upgrades of the logic and the state layout of modules after genesis.
+//!
+//! Every module has a schema version, [`Module::SCHEMA_VERSION`], which is stored in the state
+//! under a prefix derived from the module address. The runtime derived with `ApplyBlobHooks` calls
+//! [`migrate_module`] for every module at the beginning of each blob. Once the blob height, counted
+//! by [`advance_blob_height`], reaches the activation height of the runtime, [`Module::migrate`] is
+//! run exactly once and the stored version is updated.
+use crate::{Error, Module, ModuleInfo, Spec};
+use borsh::{BorshDeserialize, BorshSerialize};
+use sov_state::{Prefix, StateMap, StateValue, Storage, WorkingSet};
+
+const SCHEMA_VERSION_PREFIX: &[u8] = b"sov_modules_api/schema_version/";
+const BLOB_HEIGHT_PREFIX: &[u8] = b"sov_modules_api/blob_height";
+
+fn schema_version_value<M: ModuleInfo>(module: &M) -> StateValue<u64> {
+    let mut prefix = SCHEMA_VERSION_PREFIX.to_vec();
+    prefix.extend_from_slice(module.address().as_ref());
+    StateValue::new(Prefix::new(prefix))
+}
+
+/// Returns the schema version of the module state. Modules without a stored version are at version `0`.
+pub fn schema_version<M: ModuleInfo>(
+    module: &M,
+    working_set: &mut WorkingSet<<M::Context as Spec>::Storage>,
+) -> u64 {
+    schema_version_value(module)
+        .get(working_set)
+        .unwrap_or_default()
+}
+
+/// Records the schema version of the module state. Migrations are skipped for modules whose
+/// recorded version is not older than [`Module::SCHEMA_VERSION`].
+pub fn set_schema_version<M: ModuleInfo>(
+    module: &M,
+    version: u64,
+    working_set: &mut WorkingSet<<M::Context as Spec>::Storage>,
+) {
+    schema_version_value(module).set(&version, working_set);
+}
+
+/// Increments the number of blobs processed by the rollup and returns it. The first blob has height `1`.
+pub fn advance_blob_height<S: Storage>(working_set: &mut WorkingSet<S>) -> u64 {
+    let blob_height = StateValue::<u64>::new(Prefix::new(BLOB_HEIGHT_PREFIX.to_vec()));
+    let height = blob_height.get(working_set).unwrap_or_default() + 1;
+    blob_height.set(&height, working_set);
+    height
+}
+
+/// Runs [`Module::migrate`] if `current_height` reached `activation_height` and the module state
+/// is older than [`Module::SCHEMA_VERSION`]. Returns `true` if the migration was run.
+pub fn migrate_module<M>(
+    module: &M,
+    activation_height: u64,
+    current_height: u64,
+    working_set: &mut WorkingSet<<<M as Module>::Context as Spec>::Storage>,
+) -> Result<bool, Error>
+where
+    M: Module + ModuleInfo<Context = <M as Module>::Context>,
+{
+    if current_height < activation_height {
+        return Ok(false);
+    }
+
+    let from_version = schema_version(module, working_set);
+    if from_version >= M::SCHEMA_VERSION {
+        return Ok(false);
+    }
+
+    module.migrate(from_version, working_set)?;
+    set_schema_version(module, M::SCHEMA_VERSION, working_set);
+    Ok(true)
+}
+
+/// Moves the entries of `keys` from `old_map` to `new_map`, converting the values with `convert`.
+/// Keys absent in `old_map` are skipped. Returns the number of migrated entries.
+///
+/// The maps may share the same prefix, which rewrites the entries in place:
+/// `StateMap::<K, New>::new(old_map.prefix().clone())`.
+/// Since state maps can't be iterated, the caller has to provide the keys to migrate.
+pub fn migrate_map<K, Old, New, S: Storage>(
+    old_map: &StateMap<K, Old>,
+    new_map: &StateMap<K, New>,
+    keys: impl IntoIterator<Item = K>,
+    mut convert: impl FnMut(&K, Old) -> anyhow::Result<New>,
+    working_set: &mut WorkingSet<S>,
+) -> Result<usize, Error>
+where
+    K: BorshSerialize,
+    Old: BorshSerialize + BorshDeserialize,
+    New: BorshSerialize + BorshDeserialize,
+{
+    let mut migrated = 0;
+    for key in keys {
+        if let Some(old_value) = old_map.remove(&key, working_set) {
+            let new_value = convert(&key, old_value)?;
+            new_map.set(&key, &new_value, working_set);
+            migrated += 1;
+        }
+    }
+    Ok(migrated)
+}
+
+/// Moves the value of `old_value` to `new_value`, converting it with `convert`.
+/// Returns `false` if `old_value` is empty.
+pub fn migrate_value<Old, New, S: Storage>(
+    old_value: &StateValue<Old>,
+    new_value: &StateValue<New>,
+    convert: impl FnOnce(Old) -> anyhow::Result<New>,
+    working_set: &mut WorkingSet<S>,
+) -> Result<bool, Error>
+where
+    Old: BorshSerialize + BorshDeserialize,
+    New: BorshSerialize + BorshDeserialize,
+{
+    match old_value.remove(working_set) {
+        Some(value) => {
+            new_value.set(&convert(value)?, working_set);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
@@ -4,7 +4,7 @@ use crate::{Context, Hasher};
 const DOMAIN_SEPARATOR: [u8; 1] = [47];
 
 /// A unique identifier for each state variable in a module.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Prefix {
     module_path: &'static str,
     module_name: &'static str,
@@ -68,3 +68,70 @@ impl From<Prefix> for sov_state::Prefix {
         sov_state::Prefix::new(combined_prefix)
     }
 }
+
+/// Checks that no state prefix is equal to, or starts with, a prefix produced by a different state variable.
+/// A module nested in several other modules lists the same prefixes several times, which isn't a collision.
+///
+/// Storage derived from the prefix of a state variable (like the per-token balances of `sov-bank`) extends
+/// the prefix of its parent, so it is covered by the check of the parent prefix.
+pub fn check_prefix_collisions(prefixes: &[Prefix]) -> anyhow::Result<()> {
+    let mut combined_prefixes: Vec<(Vec<u8>, &Prefix)> = Vec::with_capacity(prefixes.len());
+    for prefix in prefixes {
+        if !combined_prefixes.iter().any(|(_, other)| *other == prefix) {
+            combined_prefixes.push((prefix.combine_prefix(), prefix));
+        }
+    }
+
+    // After sorting, a prefix that starts with another one follows it directly or follows a prefix
+    // which also starts with it.
+    combined_prefixes.sort_by(|(first, _), (second, _)| first.cmp(second));
+    for pair in combined_prefixes.windows(2) {
+        let (first_bytes, first) = &pair[0];
+        let (second_bytes, second) = &pair[1];
+        if second_bytes.starts_with(first_bytes) {
+            anyhow::bail!(
+                "The storage prefix of {:?} overlaps with the storage prefix of {:?}",
+                first,
+                second
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_prefix_collisions() {
+        let bank_tokens = Prefix::new_storage("sov_bank", "Bank", "tokens");
+        let bank_names = Prefix::new_storage("sov_bank", "Bank", "names");
+        let election = Prefix::new_storage("sov_election", "Election", "tokens");
+
+        // The same module nested twice.
+        check_prefix_collisions(&[
+            bank_tokens.clone(),
+            bank_names.clone(),
+            election,
+            bank_tokens.clone(),
+        ])
+        .unwrap();
+
+        // Different state variables with the same prefix.
+        let err = check_prefix_collisions(&[
+            bank_tokens.clone(),
+            Prefix::new_module("sov_bank/Bank", "tokens"),
+        ]);
+        assert!(err.is_err());
+
+        // A prefix of another prefix.
+        let err = check_prefix_collisions(&[
+            bank_names,
+            Prefix::new_storage("sov_bank", "Bank/tokens", "balances"),
+            bank_tokens,
+        ]);
+        assert!(err.is_err());
+    }
+}
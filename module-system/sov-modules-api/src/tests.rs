@@ -44,3 +44,252 @@ fn test_hex_conversion() {
     let deserialized_pub_key = DefaultPrivateKey::from_hex(&hex).unwrap().pub_key();
     assert_eq!(priv_key.pub_key(), deserialized_pub_key)
 }
+
+mod migration_tests {
+    use crate::default_context::DefaultContext;
+    use crate::migration::{migrate_map, migrate_module, schema_version};
+    use crate::{Address, Error, Genesis, Module, ModuleInfo, Prefix, Spec};
+    use sov_state::{ProverStorage, StateMap, WorkingSet};
+
+    type C = DefaultContext;
+
+    /// A module whose `balances` were stored as `u32` in version 0 and as `u64` since version 1.
+    struct UpgradedModule {
+        address: Address,
+        balances: StateMap<u8, u64>,
+    }
+
+    impl UpgradedModule {
+        fn balances_v0(&self) -> StateMap<u8, u32> {
+            StateMap::new(self.balances.prefix().clone())
+        }
+    }
+
+    impl ModuleInfo for UpgradedModule {
+        type Context = C;
+
+        fn new() -> Self {
+            Self {
+                address: Address::from([7; 32]),
+                balances: StateMap::new(
+                    Prefix::new_storage(module_path!(), "UpgradedModule", "balances").into(),
+                ),
+            }
+        }
+
+        fn address(&self) -> &Address {
+            &self.address
+        }
+    }
+
+    impl Module for UpgradedModule {
+        type Context = C;
+        type Config = ();
+
+        const SCHEMA_VERSION: u64 = 1;
+
+        fn migrate(
+            &self,
+            from_version: u64,
+            working_set: &mut WorkingSet<<C as Spec>::Storage>,
+        ) -> Result<(), Error> {
+            assert_eq!(from_version, 0);
+            migrate_map(
+                &self.balances_v0(),
+                &self.balances,
+                0..3,
+                |_, balance| Ok(u64::from(balance) * 1_000),
+                working_set,
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_migrate_module() {
+        const ACTIVATION_HEIGHT: u64 = 5;
+        let working_set = &mut WorkingSet::new(ProverStorage::temporary());
+        let module = UpgradedModule::new();
+
+        // The state written by version 0 of the module.
+        let balances_v0 = module.balances_v0();
+        balances_v0.set(&0, &1, working_set);
+        balances_v0.set(&2, &u32::MAX, working_set);
+        assert_eq!(schema_version(&module, working_set), 0);
+
+        assert!(!migrate_module(&module, ACTIVATION_HEIGHT, 4, working_set).unwrap());
+        assert_eq!(balances_v0.get(&0, working_set), Some(1));
+
+        assert!(migrate_module(&module, ACTIVATION_HEIGHT, 5, working_set).unwrap());
+        assert_eq!(schema_version(&module, working_set), 1);
+        assert_eq!(module.balances.get(&0, working_set), Some(1_000));
+        assert_eq!(module.balances.get(&1, working_set), None);
+        assert_eq!(
+            module.balances.get(&2, working_set),
+            Some(u64::from(u32::MAX) * 1_000)
+        );
+
+        // The migration runs only once.
+        assert!(!migrate_module(&module, ACTIVATION_HEIGHT, 6, working_set).unwrap());
+        assert_eq!(module.balances.get(&0, working_set), Some(1_000));
+    }
+
+    #[test]
+    fn test_genesis_sets_schema_version() {
+        let working_set = &mut WorkingSet::new(ProverStorage::temporary());
+        let module = UpgradedModule::new();
+
+        Genesis::genesis(&module, &(), working_set).unwrap();
+        assert_eq!(schema_version(&module, working_set), 1);
+        assert!(!migrate_module(&module, 0, 0, working_set).unwrap());
+    }
+}
+
+mod call_module_tests {
+    use crate::default_context::DefaultContext;
+    use crate::{
+        call_module, Address, CallResponse, Context, Error, Module, ModuleInfo, Prefix, Spec,
+    };
+    use sov_rollup_interface::stf::Event;
+    use sov_state::{ProverStorage, StateValue, WorkingSet};
+
+    type C = DefaultContext;
+
+    /// A module recording the last sender of a non zero value.
+    struct Recorder {
+        address: Address,
+        last_sender: StateValue<Address>,
+    }
+
+    impl ModuleInfo for Recorder {
+        type Context = C;
+
+        fn new() -> Self {
+            Self {
+                address: Address::from([8; 32]),
+                last_sender: StateValue::new(
+                    Prefix::new_storage(module_path!(), "Recorder", "last_sender").into(),
+                ),
+            }
+        }
+
+        fn address(&self) -> &Address {
+            &self.address
+        }
+
+        fn prefixes() -> Vec<Prefix> {
+            vec![Prefix::new_storage(
+                module_path!(),
+                "Recorder",
+                "last_sender",
+            )]
+        }
+    }
+
+    impl Module for Recorder {
+        type Context = C;
+        type Config = ();
+        type CallMessage = u32;
+
+        fn call(
+            &self,
+            message: u32,
+            context: &C,
+            working_set: &mut WorkingSet<<C as Spec>::Storage>,
+        ) -> Result<CallResponse, Error> {
+            self.last_sender.set(context.sender(), working_set);
+            working_set.add_event("record", &message.to_string());
+            if message == 0 {
+                return Err(anyhow::anyhow!("Zero can't be recorded").into());
+            }
+            Ok(CallResponse::default())
+        }
+    }
+
+    /// A module without state, calling the `Recorder`.
+    struct Caller {
+        address: Address,
+    }
+
+    impl ModuleInfo for Caller {
+        type Context = C;
+
+        fn new() -> Self {
+            Self {
+                address: Address::from([9; 32]),
+            }
+        }
+
+        fn address(&self) -> &Address {
+            &self.address
+        }
+    }
+
+    #[test]
+    fn test_call_module() {
+        let working_set = &mut WorkingSet::new(ProverStorage::temporary());
+        let caller = Caller::new();
+        let recorder = Recorder::new();
+
+        call_module(&caller, &recorder, 1, working_set).unwrap();
+        assert_eq!(
+            recorder.last_sender.get(working_set),
+            Some(caller.address.clone())
+        );
+        let emitter = recorder.address.to_string();
+        assert_eq!(
+            working_set.events(),
+            &[Event::new("record", "1").with_emitter(&emitter)]
+        );
+
+        // The failed call is reverted, and the caller gets its error.
+        let other_caller = Caller {
+            address: Address::from([10; 32]),
+        };
+        assert!(call_module(&other_caller, &recorder, 0, working_set).is_err());
+        assert_eq!(
+            recorder.last_sender.get(working_set),
+            Some(caller.address.clone())
+        );
+        assert_eq!(working_set.events().len(), 1);
+    }
+}
+
+#[test]
+fn test_transaction_nonce_lane_encoding() {
+    use crate::default_context::DefaultContext;
+    use crate::transaction::Transaction;
+
+    let priv_key = DefaultPrivateKey::generate();
+
+    // The main lane is never set explicitly, so it has a single encoding.
+    let tx = Transaction::<DefaultContext>::new_signed_tx(&priv_key, vec![1, 2], 3);
+    assert_eq!(
+        Transaction::<DefaultContext>::new_signed_tx_in_lane(&priv_key, vec![1, 2], 0, 3),
+        tx
+    );
+    let lane_zero = (
+        tx.signature().clone(),
+        tx.pub_key().clone(),
+        tx.runtime_msg().to_vec(),
+        tx.nonce(),
+        Some(0u64),
+    )
+        .try_to_vec()
+        .unwrap();
+    assert!(Transaction::<DefaultContext>::try_from_slice(&lane_zero).is_err());
+
+    // Transactions can be nested in other borsh types.
+    let txs = vec![
+        tx,
+        Transaction::<DefaultContext>::new_signed_tx_in_lane(&priv_key, vec![1, 2], 4, 3),
+    ];
+    let decoded =
+        Vec::<Transaction<DefaultContext>>::try_from_slice(&txs.try_to_vec().unwrap()).unwrap();
+    assert_eq!(decoded, txs);
+    assert_eq!(decoded[0].nonce_lane(), 0);
+    assert_eq!(decoded[1].nonce_lane(), 4);
+    for tx in decoded {
+        tx.verify().unwrap();
+    }
+}
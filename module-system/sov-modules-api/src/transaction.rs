@@ -7,14 +7,57 @@ use crate::Hasher;
 use crate::Signature;
 #[cfg(feature = "native")]
 use crate::Spec;
+use borsh::{BorshDeserialize, BorshSerialize};
+use std::io::{self, Read, Write};
 
 /// A Transaction object that is compatible with the module-system/sov-default-stf.
-#[derive(Debug, PartialEq, Eq, Clone, borsh::BorshDeserialize, borsh::BorshSerialize)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Transaction<C: Context> {
     signature: C::Signature,
     pub_key: C::PublicKey,
     runtime_msg: Vec<u8>,
     nonce: u64,
+    /// Transactions in different nonce lanes are sequenced independently.
+    /// Transactions without a lane use the main nonce of the account, lane `0`.
+    ///
+    /// The lane is signed only when it is set, so transactions without a lane keep the signature they had
+    /// before lanes. The main lane is never set explicitly: `Some(0)` is rejected when a transaction is decoded,
+    /// so every transaction has a single encoding.
+    nonce_lane: Option<u64>,
+}
+
+impl<C: Context> BorshSerialize for Transaction<C> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.signature.serialize(writer)?;
+        self.pub_key.serialize(writer)?;
+        self.runtime_msg.serialize(writer)?;
+        self.nonce.serialize(writer)?;
+        self.nonce_lane.serialize(writer)
+    }
+}
+
+impl<C: Context> BorshDeserialize for Transaction<C> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let signature = C::Signature::deserialize_reader(reader)?;
+        let pub_key = C::PublicKey::deserialize_reader(reader)?;
+        let runtime_msg = Vec::<u8>::deserialize_reader(reader)?;
+        let nonce = u64::deserialize_reader(reader)?;
+        let nonce_lane = Option::<u64>::deserialize_reader(reader)?;
+        if nonce_lane == Some(0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "The main nonce lane `0` must be omitted",
+            ));
+        }
+
+        Ok(Self {
+            signature,
+            pub_key,
+            runtime_msg,
+            nonce,
+            nonce_lane,
+        })
+    }
 }
 
 impl<C: Context> Transaction<C> {
@@ -34,27 +77,60 @@ impl<C: Context> Transaction<C> {
         self.nonce
     }
 
+    /// Returns the nonce lane of the transaction, `0` if it is sent in the main lane.
+    pub fn nonce_lane(&self) -> u64 {
+        self.nonce_lane.unwrap_or(0)
+    }
+
     /// Check whether the transaction has been signed correctly.
     pub fn verify(&self) -> anyhow::Result<()> {
-        // We check signature against runtime_msg and nonce.
-        let mut hasher = C::Hasher::new();
-        hasher.update(self.runtime_msg());
-        hasher.update(&self.nonce().to_le_bytes());
-        let msg_hash = hasher.finalize();
+        // We check signature against runtime_msg, nonce and nonce_lane.
+        let msg_hash = Self::msg_hash(self.runtime_msg(), self.nonce(), self.nonce_lane);
         self.signature().verify(self.pub_key(), msg_hash)?;
 
         Ok(())
     }
+
+    fn msg_hash(runtime_msg: &[u8], nonce: u64, nonce_lane: Option<u64>) -> [u8; 32] {
+        let mut hasher = C::Hasher::new();
+        hasher.update(runtime_msg);
+        hasher.update(&nonce.to_le_bytes());
+        if let Some(nonce_lane) = nonce_lane {
+            hasher.update(&nonce_lane.to_le_bytes());
+        }
+        hasher.finalize()
+    }
 }
 
 #[cfg(feature = "native")]
 impl Transaction<DefaultContext> {
     /// New signed transaction.
     pub fn new_signed_tx(priv_key: &DefaultPrivateKey, message: Vec<u8>, nonce: u64) -> Self {
-        let mut hasher = <DefaultContext as Spec>::Hasher::new();
-        hasher.update(&message);
-        hasher.update(&nonce.to_le_bytes());
-        let msg_hash = hasher.finalize();
+        Self::sign(priv_key, message, None, nonce)
+    }
+
+    /// New signed transaction sequenced in the given nonce lane, the lane `0` is the main lane.
+    pub fn new_signed_tx_in_lane(
+        priv_key: &DefaultPrivateKey,
+        message: Vec<u8>,
+        nonce_lane: u64,
+        nonce: u64,
+    ) -> Self {
+        Self::sign(
+            priv_key,
+            message,
+            (nonce_lane != 0).then_some(nonce_lane),
+            nonce,
+        )
+    }
+
+    fn sign(
+        priv_key: &DefaultPrivateKey,
+        message: Vec<u8>,
+        nonce_lane: Option<u64>,
+        nonce: u64,
+    ) -> Self {
+        let msg_hash = Self::msg_hash(&message, nonce, nonce_lane);
 
         let pub_key = priv_key.pub_key();
         let signature = priv_key.sign(msg_hash);
@@ -64,6 +140,7 @@ impl Transaction<DefaultContext> {
             runtime_msg: message,
             pub_key,
             nonce,
+            nonce_lane,
         }
     }
 
@@ -79,6 +156,7 @@ impl Transaction<DefaultContext> {
             runtime_msg: message,
             pub_key,
             nonce,
+            nonce_lane: None,
         }
     }
 }
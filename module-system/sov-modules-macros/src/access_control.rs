@@ -0,0 +1,90 @@
+use syn::{Attribute, Data, DeriveInput, Expr, Fields, Ident};
+
+const ACCESS: &str = "access";
+
+pub(crate) fn derive_access_control(
+    input: DeriveInput,
+) -> Result<proc_macro::TokenStream, syn::Error> {
+    let DeriveInput {
+        attrs,
+        data,
+        ident,
+        generics,
+        ..
+    } = input;
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    // The role is declared on each variant of an enum, or on the struct itself.
+    let required_role = match data {
+        Data::Enum(data_enum) => {
+            let match_legs = data_enum
+                .variants
+                .iter()
+                .map(|variant| {
+                    let variant_ident = &variant.ident;
+                    let pattern = match variant.fields {
+                        Fields::Named(_) => quote::quote! { Self::#variant_ident { .. } },
+                        Fields::Unnamed(_) => quote::quote! { Self::#variant_ident(..) },
+                        Fields::Unit => quote::quote! { Self::#variant_ident },
+                    };
+                    let role = make_role(&variant.attrs)?;
+
+                    Ok(quote::quote! {
+                        #pattern => #role,
+                    })
+                })
+                .collect::<Result<Vec<_>, syn::Error>>()?;
+
+            quote::quote! {
+                match self {
+                    #(#match_legs)*
+                }
+            }
+        }
+        Data::Struct(_) => make_role(&attrs)?,
+        Data::Union(un) => {
+            return Err(syn::Error::new_spanned(
+                un.union_token,
+                "The `AccessControl` macro supports structs and enums only.",
+            ))
+        }
+    };
+
+    Ok(quote::quote! {
+        impl #impl_generics sov_modules_api::access::AccessControl for #ident #type_generics #where_clause {
+            fn required_role(&self) -> ::core::option::Option<&'static str> {
+                #required_role
+            }
+        }
+    }
+    .into())
+}
+
+// Parses `#[access(role = <expr>)]`, the role can be a string literal or a constant.
+fn make_role(attrs: &[Attribute]) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let mut roles = attrs.iter().filter(|attr| attr.path.is_ident(ACCESS));
+
+    let role = match (roles.next(), roles.next()) {
+        (None, _) => return Ok(quote::quote! { ::core::option::Option::None }),
+        (Some(attr), None) => attr.parse_args_with(|input: syn::parse::ParseStream| {
+            let key: Ident = input.parse()?;
+            if key != "role" {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "Only the `role` argument is supported by the `access` attribute.",
+                ));
+            }
+            input.parse::<syn::Token![=]>()?;
+            input.parse::<Expr>()
+        })?,
+        (Some(_), Some(second)) => {
+            return Err(syn::Error::new_spanned(
+                second,
+                "The `access` attribute is defined more than once.",
+            ))
+        }
+    };
+
+    Ok(quote::quote! { ::core::option::Option::Some(#role) })
+}
@@ -1,6 +1,7 @@
-use super::common::StructFieldExtractor;
-use quote::{format_ident, quote};
-use syn::{DeriveInput, Path, PathArguments, Type};
+use super::common::{parse_generic_params, StructFieldExtractor};
+use proc_macro2::{Group, Ident, TokenStream, TokenTree};
+use quote::{format_ident, quote, ToTokens};
+use syn::{DeriveInput, GenericParam, Type, WherePredicate};
 
 pub(crate) struct CliParserMacro {
     field_extractor: StructFieldExtractor,
@@ -27,6 +28,46 @@ impl CliParserMacro {
         } = input.clone();
 
         let fields = self.field_extractor.get_fields_from_struct(&data)?;
+        let context_param = parse_generic_params(&generics)?;
+        let context_type = context_type.to_token_stream();
+
+        // The generic parameter bounded by `Context` is replaced by the concrete context everywhere,
+        // the other generic parameters of the runtime become generic parameters of the `cmd_parser` function.
+        let mut parser_generics = generics.clone();
+        parser_generics.params = generics
+            .params
+            .iter()
+            .filter(|param| !matches!(param, GenericParam::Type(ty) if ty.ident == context_param))
+            .map(|param| {
+                syn::parse2::<GenericParam>(replace_ident(
+                    param.to_token_stream(),
+                    &context_param,
+                    &context_type,
+                ))
+            })
+            .collect::<Result<_, _>>()?;
+        if let Some(where_clause) = &mut parser_generics.where_clause {
+            where_clause.predicates = where_clause
+                .predicates
+                .iter()
+                .filter(|predicate| !matches!(predicate, WherePredicate::Type(predicate) if matches!(&predicate.bounded_ty, Type::Path(ty) if ty.path.is_ident(&context_param))))
+                .map(|predicate| {
+                    syn::parse2::<WherePredicate>(replace_ident(
+                        predicate.to_token_stream(),
+                        &context_param,
+                        &context_type,
+                    ))
+                })
+                .collect::<Result<_, _>>()?;
+        }
+        let (parser_impl_generics, _, parser_where_clause) = parser_generics.split_for_impl();
+
+        let (_, type_generics, where_clause) = generics.split_for_impl();
+        let runtime_generics = replace_ident(
+            type_generics.as_turbofish().to_token_stream(),
+            &context_param,
+            &context_type,
+        );
 
         let match_arms: Vec<_> = fields
             .clone()
@@ -36,33 +77,24 @@ impl CliParserMacro {
                 let field_name_string = field_name.to_string();
                 let encode_function_name = format_ident!("encode_{}_call", field_name_string);
 
-                // TODO:
-                // For the initial version, before complicating the macro,
-                // we're assuming that each module type in Runtime only has
-                // one generic. we're removing that and appending the concrete
-                // that's passed in from the macro.
-                // We need to fix this so that:
-                // 1. Determine which generic has the Context bound
-                // 2. Identify only that generic from the module type and replace it
-                // 3. Retain other generics
-
-                // Extract the type name
-                let type_path = match &field.ty {
-                    Type::Path(type_path) => {
-                        let mut segments = type_path.path.segments.clone();
-                        let last = segments.last_mut().expect("Impossible happened! A type path has no segments");
-                        last.arguments = PathArguments::None;
-                        Path { segments, ..type_path.path.clone() }
-                    },
+                // The modules are identified by the name of their type
+                let type_name_string = match &field.ty {
+                    Type::Path(type_path) => type_path
+                        .path
+                        .segments
+                        .last()
+                        .expect("Impossible happened! A type path has no segments")
+                        .ident
+                        .to_string(),
                     _ => return Err(syn::Error::new_spanned(field.ident, "expected a type path")),
                 };
-
-                let type_name_string = type_path.segments.last().unwrap().ident.to_string();
+                let module_type =
+                    replace_ident(field.ty.to_token_stream(), &context_param, &context_type);
 
                 Ok(quote! {
                 #type_name_string => Ok({
-                    #ident::<#context_type>::#encode_function_name(
-                        serde_json::from_str::<<#type_path<#context_type> as sov_modules_api::Module>::CallMessage>(&call_data)?
+                    #ident #runtime_generics::#encode_function_name(
+                        serde_json::from_str::<<#module_type as sov_modules_api::Module>::CallMessage>(&call_data)?
                     )
                 }),
             })
@@ -76,8 +108,10 @@ impl CliParserMacro {
                 let field_name = field.ident;
                 let field_type = field.ty;
                 let field_vis = field.vis;
+                let field_attrs = field.attrs;
 
                 quote! {
+                    #(#field_attrs)*
                     #field_vis #field_name: #field_type
                 }
             })
@@ -85,11 +119,11 @@ impl CliParserMacro {
 
         let cmd_parser_tokens = quote! {
             #(#attrs)*
-            #vis struct #ident #generics {
+            #vis struct #ident #generics #where_clause {
                 #(#original_struct_fields),*
             }
 
-            pub fn cmd_parser(module_name: &str, call_data: &str) -> anyhow::Result<Vec<u8>> {
+            pub fn cmd_parser #parser_impl_generics (module_name: &str, call_data: &str) -> anyhow::Result<Vec<u8>> #parser_where_clause {
                 match module_name {
                     #(#match_arms)*
                     _ => panic!("unknown module name"),
@@ -100,3 +134,25 @@ impl CliParserMacro {
         Ok(cmd_parser_tokens.into())
     }
 }
+
+/// Replaces all the occurrences of `ident` in `tokens`. The context has to appear as a type argument,
+/// since its associated types can't be referred to through a concrete type (`DefaultContext::Address`).
+fn replace_ident(tokens: TokenStream, ident: &Ident, replacement: &TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .flat_map(|token| match token {
+            TokenTree::Ident(token_ident) if &token_ident == ident => {
+                replacement.clone().into_iter().collect::<Vec<_>>()
+            }
+            TokenTree::Group(group) => {
+                let mut replaced = Group::new(
+                    group.delimiter(),
+                    replace_ident(group.stream(), ident, replacement),
+                );
+                replaced.set_span(group.span());
+                vec![TokenTree::Group(replaced)]
+            }
+            token => vec![token],
+        })
+        .collect()
+}
@@ -1,12 +1,16 @@
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, ToTokens};
-use syn::{DataStruct, GenericParam, Generics, ImplGenerics, Meta, TypeGenerics, WhereClause};
+use syn::{
+    DataStruct, GenericParam, Generics, ImplGenerics, Meta, PredicateType, Type, TypeGenerics,
+    TypeParamBound, WhereClause, WherePredicate,
+};
 
 #[derive(Clone)]
 pub(crate) struct StructNamedField {
     pub(crate) ident: proc_macro2::Ident,
     pub(crate) ty: syn::Type,
     pub(crate) vis: syn::Visibility,
+    pub(crate) attrs: Vec<syn::Attribute>,
 }
 
 pub(crate) struct StructFieldExtractor {
@@ -58,6 +62,7 @@ impl StructFieldExtractor {
                 ident: field_ident.clone(),
                 ty: original_field.ty.clone(),
                 vis: original_field.vis.clone(),
+                attrs: original_field.attrs.clone(),
             };
 
             output_fields.push(field);
@@ -68,6 +73,25 @@ impl StructFieldExtractor {
 
 pub(crate) const CALL: &str = "Call";
 
+/// Checks that the state of the modules doesn't overlap. This can't be checked at compile time because
+/// the prefixes are built from `module_path!`. The generated expression is an `anyhow::Result<()>`.
+pub(crate) fn make_check_prefixes(fields: &[StructNamedField]) -> TokenStream {
+    let types = fields.iter().map(|field| &field.ty);
+
+    quote::quote! {
+        ::sov_modules_api::check_prefix_collisions(
+            &::std::iter::empty()
+                #(.chain(<#types as ::sov_modules_api::ModuleInfo>::prefixes()))*
+                .collect::<::std::vec::Vec<_>>(),
+        )
+    }
+}
+
+/// Checks if the field is marked with the attribute `#[<name>]`.
+pub(crate) fn has_attribute(field: &StructNamedField, name: &str) -> bool {
+    field.attrs.iter().any(|attr| attr.path.is_ident(name))
+}
+
 /// Represents "parsed" rust struct.
 pub(crate) struct StructDef<'a> {
     pub(crate) ident: proc_macro2::Ident,
@@ -103,6 +127,7 @@ impl<'a> StructDef<'a> {
         enum_legs: &[proc_macro2::TokenStream],
         postfix: &'static str,
         serialization_attrs: &Vec<TokenStream>,
+        extra_attrs: &[TokenStream],
     ) -> proc_macro2::TokenStream {
         let enum_ident = self.enum_ident(postfix);
         let impl_generics = &self.impl_generics;
@@ -111,6 +136,7 @@ impl<'a> StructDef<'a> {
         quote::quote! {
             #[allow(non_camel_case_types)]
             #[derive(::core::fmt::Debug, PartialEq, #(#serialization_attrs),*)]
+            #(#extra_attrs)*
             pub enum #enum_ident #impl_generics #where_clause {
                 #(#enum_legs)*
             }
@@ -123,35 +149,88 @@ impl<'a> StructDef<'a> {
     }
 }
 
-/// Gets type parameter from `Generics` declaration.
+/// Gets the type parameter bounded by `Context` from a `Generics` declaration. The bound can either be inline
+/// (`C: Context`) or in the where clause (`where C: Context`). The other type parameters are left to the derived type.
+///
+/// Macros can't resolve paths, so the bound is recognized by the last segment of its path: `Context` and
+/// `sov_modules_api::Context` are both found, but the trait imported under another name (`use Context as Ctx`)
+/// is not, and any other trait named `Context` is mistaken for it.
 pub(crate) fn parse_generic_params(generics: &Generics) -> Result<Ident, syn::Error> {
-    let generic_param = match generics.params.first().unwrap() {
-        GenericParam::Type(ty) => &ty.ident,
-        GenericParam::Lifetime(lf) => {
-            return Err(syn::Error::new_spanned(
-                lf,
-                "Lifetime parameters not supported.",
-            ))
-        }
-        GenericParam::Const(cnst) => {
-            return Err(syn::Error::new_spanned(
-                cnst,
-                "Const parameters not supported.",
-            ))
+    let mut context_params = Vec::new();
+    for param in &generics.params {
+        match param {
+            GenericParam::Type(ty) => {
+                if ty.bounds.iter().any(is_context_bound)
+                    || has_where_context_bound(generics, &ty.ident)
+                {
+                    context_params.push(&ty.ident);
+                }
+            }
+            GenericParam::Lifetime(lf) => {
+                return Err(syn::Error::new_spanned(
+                    lf,
+                    "Lifetime parameters not supported.",
+                ))
+            }
+            GenericParam::Const(cnst) => {
+                return Err(syn::Error::new_spanned(
+                    cnst,
+                    "Const parameters not supported.",
+                ))
+            }
         }
-    };
+    }
+
+    match context_params.as_slice() {
+        [context_param] => Ok((*context_param).clone()),
+        [] => Err(syn::Error::new(
+            Span::call_site(),
+            "One generic parameter must be bounded by `Context`.",
+        )),
+        [_, second, ..] => Err(syn::Error::new_spanned(
+            second,
+            "Only one generic parameter can be bounded by `Context`.",
+        )),
+    }
+}
+
+fn has_where_context_bound(generics: &Generics, ident: &Ident) -> bool {
+    generics.where_clause.iter().any(|where_clause| {
+        where_clause
+            .predicates
+            .iter()
+            .any(|predicate| match predicate {
+                WherePredicate::Type(PredicateType {
+                    bounded_ty: Type::Path(bounded_ty),
+                    bounds,
+                    ..
+                }) => bounded_ty.path.is_ident(ident) && bounds.iter().any(is_context_bound),
+                _ => false,
+            })
+    })
+}
 
-    Ok(generic_param.clone())
+/// Whether the last segment of the path of the trait bound is `Context`, see [`parse_generic_params`].
+fn is_context_bound(bound: &TypeParamBound) -> bool {
+    match bound {
+        TypeParamBound::Trait(bound) => bound
+            .path
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == "Context"),
+        TypeParamBound::Lifetime(_) => false,
+    }
 }
 
 pub fn get_attribute_values(item: &syn::DeriveInput, attribute_name: &str) -> Vec<TokenStream> {
     let mut values = vec![];
 
-    // Find the attribute with the given name on the root item
-    if let Some(attr) = item
+    // Find the attributes with the given name on the root item, an item can have several of them,
+    // for example when some values are only enabled with `cfg_attr`.
+    for attr in item
         .attrs
         .iter()
-        .find(|attr| attr.path.is_ident(attribute_name))
+        .filter(|attr| attr.path.is_ident(attribute_name))
     {
         if let Ok(meta) = attr.parse_meta() {
             if let Meta::List(list) = meta {
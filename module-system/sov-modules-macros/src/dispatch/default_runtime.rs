@@ -0,0 +1,68 @@
+use super::common::{make_check_prefixes, StructFieldExtractor};
+use syn::DeriveInput;
+
+pub(crate) struct DefaultRuntimeMacro {
+    field_extractor: StructFieldExtractor,
+}
+
+impl DefaultRuntimeMacro {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self {
+            field_extractor: StructFieldExtractor::new(name),
+        }
+    }
+
+    pub(crate) fn derive_default_runtime(
+        &self,
+        input: DeriveInput,
+    ) -> Result<proc_macro::TokenStream, syn::Error> {
+        let DeriveInput {
+            data,
+            ident,
+            generics,
+            ..
+        } = input;
+
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+        let fields = self.field_extractor.get_fields_from_struct(&data)?;
+
+        let field_values = fields.iter().map(|field| {
+            let name = &field.ident;
+            let ty = &field.ty;
+
+            quote::quote! {
+                #name: <#ty as ::sov_modules_api::ModuleInfo>::new(),
+            }
+        });
+
+        let check_prefixes = make_check_prefixes(&fields);
+
+        Ok(quote::quote! {
+            impl #impl_generics #ident #type_generics #where_clause {
+                #[doc = "Creates the runtime, all the modules are created with `ModuleInfo::new`."]
+                #[doc = ""]
+                #[doc = "Panics if the state of two modules overlaps, see `check_prefix_collisions`."]
+                pub fn new() -> Self {
+                    if let Err(e) = Self::check_prefix_collisions() {
+                        panic!("Invalid runtime: {}", e);
+                    }
+                    Self {
+                        #(#field_values)*
+                    }
+                }
+
+                #[doc = "Fails if the storage prefixes of two state variables of the modules are equal, or if one is a prefix of the other."]
+                pub fn check_prefix_collisions() -> ::anyhow::Result<()> {
+                    #check_prefixes
+                }
+            }
+
+            impl #impl_generics ::core::default::Default for #ident #type_generics #where_clause {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        }
+        .into())
+    }
+}
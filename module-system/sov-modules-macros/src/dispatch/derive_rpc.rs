@@ -478,3 +478,75 @@ pub(crate) fn rpc_outer_impls(
 
     Ok(output_tokens.into())
 }
+
+pub(crate) const RPC: &str = "rpc";
+
+/// Generates the RPC storage and the `get_rpc_methods` function for the modules of the runtime marked with `#[rpc]`.
+/// The `<Module>RpcImpl` and `<Module>RpcServer` traits generated by `rpc_gen` must be in scope.
+pub(crate) fn rpc_runtime_impls(
+    args: proc_macro2::TokenStream,
+    mut input: syn::DeriveInput,
+) -> Result<proc_macro::TokenStream, syn::Error> {
+    let context_type: Type = syn::parse2(args)?;
+
+    let fields = match &mut input.data {
+        syn::Data::Struct(data_struct) => &mut data_struct.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "The expose_rpc macro supports structs only.",
+            ))
+        }
+    };
+
+    let mut rpc_impls = vec![];
+    let mut merge_operations = vec![];
+    for field in fields.iter_mut() {
+        let rpc_attrs = field.attrs.len();
+        field.attrs.retain(|attr| !attr.path.is_ident(RPC));
+        if field.attrs.len() == rpc_attrs {
+            continue;
+        }
+
+        let module_ident = match &field.ty {
+            Type::Path(type_path) => &type_path.path.segments.last().unwrap().ident,
+            _ => return Err(syn::Error::new_spanned(&field.ty, "Expected a path type")),
+        };
+        let rpc_impl_ident = format_ident!("{}RpcImpl", module_ident);
+        let rpc_server_ident = format_ident!("{}RpcServer", module_ident);
+
+        rpc_impls.push(quote! {
+            impl #rpc_impl_ident<#context_type> for RpcStorage<#context_type> {
+                fn get_working_set(&self) -> ::sov_state::WorkingSet<<#context_type
+                    as ::sov_modules_api::Spec>::Storage> {
+                    ::sov_state::WorkingSet::new(self.storage.clone())
+                }
+            }
+        });
+        merge_operations.push(quote! {
+            module
+                .merge(#rpc_server_ident::<#context_type>::into_rpc(storage.clone()))
+                .unwrap();
+        });
+    }
+
+    Ok(quote! {
+        #input
+
+        #[derive(Clone)]
+        pub struct RpcStorage<C: ::sov_modules_api::Context> {
+            pub storage: C::Storage,
+        }
+
+        #(#rpc_impls)*
+
+        pub fn get_rpc_methods(storage: <#context_type as ::sov_modules_api::Spec>::Storage) -> ::jsonrpsee::RpcModule<()> {
+            let mut module = ::jsonrpsee::RpcModule::new(());
+            let storage = RpcStorage::<#context_type> { storage };
+
+            #(#merge_operations)*
+            module
+        }
+    }
+    .into())
+}
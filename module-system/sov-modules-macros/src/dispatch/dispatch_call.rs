@@ -20,6 +20,46 @@ impl<'a> StructDef<'a> {
             .collect()
     }
 
+    /// The call messages are only known to implement serde traits for some contexts (for example, the native one),
+    /// so the bounds derived by serde on the generic parameter are replaced with bounds on the call messages.
+    /// The serde derives are recognized by the last segment of their path, so re-exported paths match as well.
+    fn create_call_enum_serde_bound(
+        &self,
+        serialization_attrs: &[proc_macro2::TokenStream],
+    ) -> Option<proc_macro2::TokenStream> {
+        let find_derive = |name: &str| {
+            serialization_attrs.iter().find_map(|attr| {
+                let path = syn::parse2::<syn::Path>(attr.clone()).ok()?;
+                let is_derive = path.segments.last()?.ident == name;
+                is_derive.then(|| quote::quote!(#path).to_string())
+            })
+        };
+        let serialize = find_derive("Serialize")?;
+        let deserialize = find_derive("Deserialize")?;
+
+        let call_messages: Vec<String> = self
+            .fields
+            .iter()
+            .map(|field| {
+                let ty = &field.ty;
+                quote::quote!(<#ty as sov_modules_api::Module>::CallMessage).to_string()
+            })
+            .collect();
+        let bound = |serde_trait: &str| {
+            call_messages
+                .iter()
+                .map(|call_message| format!("{call_message}: {serde_trait}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let serialize_bound = bound(&serialize);
+        let deserialize_bound = bound(&format!("{deserialize}<'de>"));
+
+        Some(quote::quote! {
+            #[serde(bound(serialize = #serialize_bound, deserialize = #deserialize_bound))]
+        })
+    }
+
     fn create_call_dispatch(&self) -> proc_macro2::TokenStream {
         let enum_ident = self.enum_ident(CALL);
         let type_generics = &self.type_generics;
@@ -29,6 +69,7 @@ impl<'a> StructDef<'a> {
 
             quote::quote!(
                 #enum_ident::#name(message)=>{
+                    sov_modules_api::Module::check_access(&self.#name, &message, context, working_set)?;
                     sov_modules_api::Module::call(&self.#name, message, context, working_set)
                 },
             )
@@ -126,7 +167,13 @@ impl DispatchCallMacro {
         );
 
         let call_enum_legs = struct_def.create_call_enum_legs();
-        let call_enum = struct_def.create_enum(&call_enum_legs, CALL, &serialization_methods);
+        let serde_bound = struct_def.create_call_enum_serde_bound(&serialization_methods);
+        let call_enum = struct_def.create_enum(
+            &call_enum_legs,
+            CALL,
+            &serialization_methods,
+            serde_bound.as_slice(),
+        );
         let create_dispatch_impl = struct_def.create_call_dispatch();
 
         Ok(quote::quote! {
@@ -1,7 +1,6 @@
 use super::common::parse_generic_params;
-use super::common::{StructFieldExtractor, StructNamedField};
-use proc_macro2::Ident;
-use syn::{DeriveInput, TypeGenerics};
+use super::common::{make_check_prefixes, StructFieldExtractor, StructNamedField};
+use syn::{DeriveInput, ImplGenerics, TypeGenerics, WhereClause};
 
 pub(crate) struct GenesisMacro {
     field_extractor: StructFieldExtractor,
@@ -29,8 +28,10 @@ impl GenesisMacro {
 
         let fields = self.field_extractor.get_fields_from_struct(&data)?;
         let generic_param = parse_generic_params(&generics)?;
-        let genesis_config = Self::make_genesis_config(&fields, &type_generics, &generic_param);
+        let genesis_config =
+            Self::make_genesis_config(&fields, &impl_generics, &type_generics, where_clause);
         let genesis_fn_body = Self::make_genesis_fn_body(&fields);
+        let check_prefixes = make_check_prefixes(&fields);
 
         // Implements the Genesis trait
         Ok(quote::quote! {
@@ -41,6 +42,7 @@ impl GenesisMacro {
                 type Config = GenesisConfig #type_generics;
 
                 fn genesis(&self, config: &Self::Config, working_set: &mut sov_state::WorkingSet<<<Self as sov_modules_api::Genesis>::Context as sov_modules_api::Spec>::Storage>) -> core::result::Result<(), sov_modules_api::Error> {
+                    #check_prefixes?;
                     #(#genesis_fn_body)*
                     Ok(())
                 }
@@ -64,8 +66,9 @@ impl GenesisMacro {
 
     fn make_genesis_config(
         fields: &[StructNamedField],
+        impl_generics: &ImplGenerics,
         type_generics: &TypeGenerics,
-        generic_param: &Ident,
+        where_clause: Option<&WhereClause>,
     ) -> proc_macro2::TokenStream {
         let field_names = fields.iter().map(|field| &field.ident);
 
@@ -83,11 +86,11 @@ impl GenesisMacro {
 
         quote::quote! {
             #[doc = "Initial configuration for the rollup."]
-            pub struct GenesisConfig<#generic_param: sov_modules_api::Context>{
+            pub struct GenesisConfig #impl_generics #where_clause {
                 #(pub #fields)*
             }
 
-            impl<#generic_param: sov_modules_api::Context> GenesisConfig #type_generics {
+            impl #impl_generics GenesisConfig #type_generics #where_clause {
                 pub fn new(#(#fields)*) -> Self {
                     Self {
                         #(#field_names),*
@@ -0,0 +1,194 @@
+use super::common::{has_attribute, parse_generic_params, StructFieldExtractor};
+use proc_macro2::Span;
+use syn::{DeriveInput, Type};
+
+pub(crate) const TX_HOOKS: &str = "tx_hooks";
+pub(crate) const BLOB_HOOKS: &str = "blob_hooks";
+pub(crate) const BLOB_RESULT: &str = "blob_result";
+pub(crate) const MIGRATION_HEIGHT: &str = "migration_height";
+
+pub(crate) struct TxHooksMacro {
+    field_extractor: StructFieldExtractor,
+}
+
+impl TxHooksMacro {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self {
+            field_extractor: StructFieldExtractor::new(name),
+        }
+    }
+
+    pub(crate) fn derive_tx_hooks(
+        &self,
+        input: DeriveInput,
+    ) -> Result<proc_macro::TokenStream, syn::Error> {
+        let DeriveInput {
+            data,
+            ident,
+            generics,
+            ..
+        } = input;
+
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+        let fields = self.field_extractor.get_fields_from_struct(&data)?;
+        let generic_param = parse_generic_params(&generics)?;
+
+        // The transaction sender is returned by `pre_dispatch_tx_hook`, so a single module is responsible for the hooks.
+        let mut hooks_fields = fields.iter().filter(|field| has_attribute(field, TX_HOOKS));
+        let hooks_field = match (hooks_fields.next(), hooks_fields.next()) {
+            (Some(field), None) => &field.ident,
+            (None, _) => {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "One module must be marked with `#[tx_hooks]`.",
+                ))
+            }
+            (Some(_), Some(second)) => {
+                return Err(syn::Error::new_spanned(
+                    &second.ident,
+                    "Only one module can be marked with `#[tx_hooks]`.",
+                ))
+            }
+        };
+
+        Ok(quote::quote! {
+            impl #impl_generics ::sov_modules_api::hooks::TxHooks for #ident #type_generics #where_clause {
+                type Context = #generic_param;
+
+                fn pre_dispatch_tx_hook(
+                    &self,
+                    tx: ::sov_modules_api::transaction::Transaction<Self::Context>,
+                    working_set: &mut ::sov_state::WorkingSet<<Self::Context as ::sov_modules_api::Spec>::Storage>,
+                ) -> ::anyhow::Result<<Self::Context as ::sov_modules_api::Spec>::Address> {
+                    ::sov_modules_api::hooks::TxHooks::pre_dispatch_tx_hook(&self.#hooks_field, tx, working_set)
+                }
+
+                fn pre_dispatch_call_hook(
+                    &self,
+                    tx: &::sov_modules_api::transaction::Transaction<Self::Context>,
+                    module_address: &<Self::Context as ::sov_modules_api::Spec>::Address,
+                    working_set: &mut ::sov_state::WorkingSet<<Self::Context as ::sov_modules_api::Spec>::Storage>,
+                ) -> ::anyhow::Result<()> {
+                    ::sov_modules_api::hooks::TxHooks::pre_dispatch_call_hook(&self.#hooks_field, tx, module_address, working_set)
+                }
+
+                fn post_dispatch_tx_hook(
+                    &self,
+                    tx: &::sov_modules_api::transaction::Transaction<Self::Context>,
+                    working_set: &mut ::sov_state::WorkingSet<<Self::Context as ::sov_modules_api::Spec>::Storage>,
+                ) -> ::anyhow::Result<()> {
+                    ::sov_modules_api::hooks::TxHooks::post_dispatch_tx_hook(&self.#hooks_field, tx, working_set)
+                }
+            }
+        }
+        .into())
+    }
+}
+
+pub(crate) struct ApplyBlobHooksMacro {
+    field_extractor: StructFieldExtractor,
+}
+
+impl ApplyBlobHooksMacro {
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self {
+            field_extractor: StructFieldExtractor::new(name),
+        }
+    }
+
+    pub(crate) fn derive_apply_blob_hooks(
+        &self,
+        input: DeriveInput,
+    ) -> Result<proc_macro::TokenStream, syn::Error> {
+        let DeriveInput {
+            attrs,
+            data,
+            ident,
+            generics,
+            ..
+        } = input;
+
+        let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+        let fields = self.field_extractor.get_fields_from_struct(&data)?;
+        let generic_param = parse_generic_params(&generics)?;
+
+        let blob_result: Type = attrs
+            .iter()
+            .find(|attr| attr.path.is_ident(BLOB_RESULT))
+            .ok_or_else(|| {
+                syn::Error::new(
+                    Span::call_site(),
+                    "The blob result of the runtime must be set with `#[blob_result(<type>)]`.",
+                )
+            })?
+            .parse_args()?;
+
+        // The modules are migrated once the blob height reaches `#[migration_height(<expr>)]`, from the first blob by default.
+        let migration_height: syn::Expr = match attrs
+            .iter()
+            .find(|attr| attr.path.is_ident(MIGRATION_HEIGHT))
+        {
+            Some(attr) => attr.parse_args()?,
+            None => syn::parse_quote!(0),
+        };
+        let module_fields = fields.iter().map(|field| &field.ident);
+
+        // The hooks run in the order of the fields. Each module gets a clone of the blob result, or the value
+        // returned by the mapping function given with `#[blob_hooks(<fn>)]` for a reference to the blob result.
+        let mut hooks_fields = Vec::new();
+        let mut module_results = Vec::new();
+        for field in &fields {
+            let Some(attr) = field.attrs.iter().find(|attr| attr.path.is_ident(BLOB_HOOKS)) else {
+                continue;
+            };
+            let module_result = if attr.tokens.is_empty() {
+                quote::quote!(::core::clone::Clone::clone(&result))
+            } else {
+                let map: syn::Expr = attr.parse_args()?;
+                quote::quote!((#map)(&result))
+            };
+            hooks_fields.push(&field.ident);
+            module_results.push(module_result);
+        }
+
+        Ok(quote::quote! {
+            impl #impl_generics ::sov_modules_api::hooks::ApplyBlobHooks for #ident #type_generics #where_clause {
+                type Context = #generic_param;
+                type BlobResult = #blob_result;
+
+                fn begin_blob_hook(
+                    &self,
+                    sequencer: &[u8],
+                    raw_blob: &[u8],
+                    working_set: &mut ::sov_state::WorkingSet<<Self::Context as ::sov_modules_api::Spec>::Storage>,
+                ) -> ::anyhow::Result<()> {
+                    let blob_height = ::sov_modules_api::migration::advance_blob_height(working_set);
+                    #(
+                        ::sov_modules_api::migration::migrate_module(&self.#module_fields, #migration_height, blob_height, working_set)?;
+                    )*
+                    #(
+                        ::sov_modules_api::hooks::ApplyBlobHooks::begin_blob_hook(&self.#hooks_fields, sequencer, raw_blob, working_set)?;
+                    )*
+                    Ok(())
+                }
+
+                #[allow(unused_variables)]
+                fn end_blob_hook(
+                    &self,
+                    result: Self::BlobResult,
+                    working_set: &mut ::sov_state::WorkingSet<<Self::Context as ::sov_modules_api::Spec>::Storage>,
+                ) -> ::anyhow::Result<()> {
+                    #(
+                        ::sov_modules_api::hooks::ApplyBlobHooks::end_blob_hook(
+                            &self.#hooks_fields,
+                            #module_results,
+                            working_set,
+                        )?;
+                    )*
+                    Ok(())
+                }
+            }
+        }
+        .into())
+    }
+}
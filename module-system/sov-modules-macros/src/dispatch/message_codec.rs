@@ -7,6 +7,7 @@ impl<'a> StructDef<'a> {
     fn create_message_codec(&self) -> TokenStream {
         let call_enum = self.enum_ident(CALL);
         let ty_generics = &self.type_generics;
+        let call_enum_generics = ty_generics.as_turbofish();
 
         let fns = self.fields.iter().map(|field| {
             let variant = &field.ident;
@@ -23,7 +24,7 @@ impl<'a> StructDef<'a> {
             quote::quote! {
                 #[doc = #call_doc]
                 pub fn #fn_call_name(data: <#ty as sov_modules_api::Module>::CallMessage) -> std::vec::Vec<u8> {
-                    let call = #call_enum::<C>::#variant(data);
+                    let call = #call_enum #call_enum_generics::#variant(data);
                     ::borsh::BorshSerialize::try_to_vec(&call).unwrap()
                 }
             }
@@ -1,6 +1,8 @@
 pub mod cli_parser;
 pub(crate) mod common;
+pub(crate) mod default_runtime;
 pub mod derive_rpc;
 pub mod dispatch_call;
 pub mod genesis;
+pub(crate) mod hooks;
 pub mod message_codec;
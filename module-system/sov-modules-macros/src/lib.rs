@@ -1,9 +1,14 @@
 #![feature(log_syntax)]
+mod access_control;
 mod dispatch;
 mod module_info;
 use crate::dispatch::cli_parser::CliParserMacro;
 use dispatch::{
-    dispatch_call::DispatchCallMacro, genesis::GenesisMacro, message_codec::MessageCodec,
+    default_runtime::DefaultRuntimeMacro,
+    dispatch_call::DispatchCallMacro,
+    genesis::GenesisMacro,
+    hooks::{ApplyBlobHooksMacro, TxHooksMacro},
+    message_codec::MessageCodec,
 };
 use proc_macro::TokenStream;
 use syn::parse_macro_input;
@@ -40,6 +45,28 @@ pub fn module_info(input: TokenStream) -> TokenStream {
     handle_macro_error(module_info::derive_module_info(input))
 }
 
+/// Derives the `sov-modules-api::access::AccessControl` implementation for a call message.
+///
+/// The role required to send a message is declared with `#[access(role = ...)]` on the variants of an enum,
+/// or on a struct. The messages without the attribute can be sent by anyone.
+/// ## Example
+///
+/// ``` ignore
+///  #[derive(AccessControl)]
+///  pub enum CallMessage<C: Context> {
+///     #[access(role = "admin")]
+///     AddVoter(C::Address),
+///
+///     Vote(usize),
+///  }
+/// ```
+#[proc_macro_derive(AccessControl, attributes(access))]
+pub fn access_control(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input);
+
+    handle_macro_error(access_control::derive_access_control(input))
+}
+
 /// Derives the `sov-modules-api::Genesis` implementation for the underlying type.
 #[proc_macro_derive(Genesis)]
 pub fn genesis(input: TokenStream) -> TokenStream {
@@ -50,7 +77,11 @@ pub fn genesis(input: TokenStream) -> TokenStream {
 }
 
 /// Derives the `sov-modules-api::DispatchCall` implementation for the underlying type.
-#[proc_macro_derive(DispatchCall, attributes(serialization))]
+///
+/// The `rpc` field attribute is consumed by `expose_rpc`, which is an attribute macro and can't declare it.
+/// Every runtime derives `DispatchCall`, so the attribute is accepted here, and the runtime still compiles
+/// when `expose_rpc` is only enabled for some features.
+#[proc_macro_derive(DispatchCall, attributes(serialization, rpc))]
 pub fn dispatch_call(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input);
     let call_macro = DispatchCallMacro::new("Call");
@@ -67,6 +98,58 @@ pub fn codec(input: TokenStream) -> TokenStream {
     handle_macro_error(codec_macro.derive_message_codec(input))
 }
 
+/// Generates the `new` constructor and the `Default` implementation of the runtime, every module is created with
+/// `sov-modules-api::ModuleInfo::new`.
+#[proc_macro_derive(DefaultRuntime)]
+pub fn default_runtime(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input);
+    let default_runtime_macro = DefaultRuntimeMacro::new("DefaultRuntime");
+
+    handle_macro_error(default_runtime_macro.derive_default_runtime(input))
+}
+
+/// Derives the `sov-modules-api::hooks::TxHooks` implementation for the runtime by forwarding the hooks
+/// to the module marked with `#[tx_hooks]`.
+#[proc_macro_derive(TxHooks, attributes(tx_hooks))]
+pub fn tx_hooks(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input);
+    let tx_hooks_macro = TxHooksMacro::new("TxHooks");
+
+    handle_macro_error(tx_hooks_macro.derive_tx_hooks(input))
+}
+
+/// Derives the `sov-modules-api::hooks::ApplyBlobHooks` implementation for the runtime by forwarding the hooks
+/// to the modules marked with `#[blob_hooks]`, in the order of the fields.
+///
+/// The blob result of the runtime is set with `#[blob_result(<type>)]`. A module marked with `#[blob_hooks]` gets
+/// a clone of it, so its blob result must be the same type. Otherwise, the blob result of the module is computed with
+/// the function given with `#[blob_hooks(<fn>)]`, which takes a reference to the blob result of the runtime.
+///
+/// Before the hooks, `begin_blob_hook` counts the blob and runs `sov_modules_api::migration::migrate_module` for
+/// every module of the runtime. The migrations are activated at the blob height set with `#[migration_height(<expr>)]`,
+/// or at the first blob if the attribute is missing.
+///
+/// ## Example
+///
+/// ``` ignore
+///  #[derive(ApplyBlobHooks)]
+///  #[blob_result(SequencerOutcome)]
+///  #[migration_height(MIGRATION_HEIGHT)]
+///  pub struct Runtime<C: Context> {
+///     #[blob_hooks(sequencer_reward)]
+///     pub sequencer: sov_sequencer_registry::Sequencer<C>,
+///
+///     pub bank: sov_bank::Bank<C>,
+///  }
+/// ```
+#[proc_macro_derive(ApplyBlobHooks, attributes(blob_hooks, blob_result, migration_height))]
+pub fn apply_blob_hooks(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input);
+    let apply_blob_hooks_macro = ApplyBlobHooksMacro::new("ApplyBlobHooks");
+
+    handle_macro_error(apply_blob_hooks_macro.derive_apply_blob_hooks(input))
+}
+
 /// Derive a `jsonrpsee` implementation for the underlying type. Any code relying on this macro
 /// must take jsonrpsee as a dependency with at least the following features enabled: `["macros", "client-core", "server"]`.
 ///
@@ -148,12 +231,24 @@ fn handle_macro_error(result: Result<proc_macro::TokenStream, syn::Error>) -> To
 
 /// This proc macro generates the actual implementations for the trait created above for the module
 /// It iterates over each struct
+///
+/// When applied to the runtime struct, the attribute takes the context type (`#[expose_rpc(DefaultContext)]`)
+/// and exposes the RPC methods of the modules marked with `#[rpc]`.
 #[proc_macro_attribute]
 pub fn expose_rpc(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(item as syn::ItemImpl);
-    handle_macro_error(
-        dispatch::derive_rpc::rpc_outer_impls(attr.into(), input).map(|ok| ok.into()),
-    )
+    let input = parse_macro_input!(item as syn::Item);
+    match input {
+        syn::Item::Impl(input) => handle_macro_error(
+            dispatch::derive_rpc::rpc_outer_impls(attr.into(), input).map(|ok| ok.into()),
+        ),
+        syn::Item::Struct(input) => handle_macro_error(dispatch::derive_rpc::rpc_runtime_impls(
+            attr.into(),
+            input.into(),
+        )),
+        item => syn::Error::new_spanned(item, "expose_rpc supports structs and impl blocks only.")
+            .to_compile_error()
+            .into(),
+    }
 }
 
 #[proc_macro_attribute]
@@ -96,16 +96,20 @@ impl<'a> StructDef<'a> {
         let mut impl_self_init = Vec::default();
         let mut impl_self_body = Vec::default();
 
+        let mut impl_prefixes = Vec::default();
+
         let mut module_address = None;
         for field in fields.iter() {
             match field {
                 FieldKind::State(field) => {
                     impl_self_init.push(make_init_state(field)?);
                     impl_self_body.push(&field.ident);
+                    impl_prefixes.push(make_state_prefixes(field));
                 }
                 FieldKind::Module(field) => {
                     impl_self_init.push(make_init_module(field)?);
                     impl_self_body.push(&field.ident);
+                    impl_prefixes.push(make_module_prefixes(field));
                 }
                 FieldKind::Address(field) => {
                     impl_self_init.push(make_init_address(field, &self.ident, module_address)?);
@@ -139,6 +143,13 @@ impl<'a> StructDef<'a> {
                 }
 
                 #fn_address
+
+                fn prefixes() -> ::std::vec::Vec<sov_modules_api::Prefix> {
+                    #[allow(unused_mut)]
+                    let mut prefixes = ::std::vec::Vec::new();
+                    #(#impl_prefixes)*
+                    prefixes
+                }
             }
         })
     }
@@ -278,6 +289,22 @@ fn make_init_state(field: &StructNamedField) -> Result<proc_macro2::TokenStream,
     })
 }
 
+fn make_state_prefixes(field: &StructNamedField) -> proc_macro2::TokenStream {
+    let prefix_fun = prefix_func_ident(&field.ident);
+
+    quote::quote! {
+        prefixes.push(Self::#prefix_fun());
+    }
+}
+
+fn make_module_prefixes(field: &StructNamedField) -> proc_macro2::TokenStream {
+    let ty = &field.ty;
+
+    quote::quote! {
+        prefixes.extend(<#ty as sov_modules_api::ModuleInfo>::prefixes());
+    }
+}
+
 fn make_init_module(field: &StructNamedField) -> Result<proc_macro2::TokenStream, syn::Error> {
     let field_ident = &field.ident;
     let ty = &field.ty;
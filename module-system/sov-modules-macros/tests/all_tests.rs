@@ -9,6 +9,7 @@ fn module_info_tests() {
     t.compile_fail("tests/module_info/not_supported_type.rs");
     t.compile_fail("tests/module_info/second_addr_not_supported.rs");
     t.compile_fail("tests/module_info/missing_address.rs");
+    t.compile_fail("tests/module_info/missing_context_bound.rs");
 }
 
 #[test]
@@ -17,6 +18,11 @@ fn module_dispatch_tests() {
     t.pass("tests/dispatch/derive_genesis.rs");
     t.pass("tests/dispatch/derive_rpc.rs");
     t.pass("tests/dispatch/derive_dispatch.rs");
+    t.pass("tests/dispatch/derive_dispatch_serde.rs");
+    t.pass("tests/dispatch/derive_dispatch_extra_generics.rs");
+    t.pass("tests/dispatch/derive_runtime_hooks.rs");
+    t.pass("tests/dispatch/genesis_prefix_collision.rs");
+    t.compile_fail("tests/dispatch/second_tx_hooks.rs");
     t.compile_fail("tests/dispatch/missing_serialization.rs");
 }
 
@@ -24,4 +30,6 @@ fn module_dispatch_tests() {
 fn rpc_tests() {
     let t = trybuild::TestCases::new();
     t.pass("tests/dispatch/derive_rpc.rs");
+    t.pass("tests/dispatch/expose_rpc_runtime.rs");
+    t.pass("tests/dispatch/gated_expose_rpc.rs");
 }
@@ -0,0 +1,66 @@
+mod modules;
+use modules::first_test_module;
+use modules::third_test_module::{self, ModuleThreeStorable};
+use sov_modules_api::Address;
+use sov_modules_api::ModuleInfo;
+use sov_modules_api::{default_context::DefaultContext, Context, Genesis};
+use sov_modules_macros::{cli_parser, DispatchCall, Genesis, MessageCodec};
+use sov_state::ProverStorage;
+
+// The generic parameter bounded by `Context` doesn't have to be the first one.
+#[cli_parser(DefaultContext)]
+#[derive(Genesis, DispatchCall, MessageCodec)]
+#[serialization(borsh::BorshDeserialize, borsh::BorshSerialize)]
+struct Runtime<T, C>
+where
+    T: ModuleThreeStorable,
+    C: Context,
+{
+    pub first: first_test_module::FirstTestStruct<C>,
+    pub third: third_test_module::ThirdTestStruct<C, T>,
+}
+
+impl<T: ModuleThreeStorable, C: Context> Runtime<T, C> {
+    fn new() -> Self {
+        Self {
+            first: first_test_module::FirstTestStruct::<C>::new(),
+            third: third_test_module::ThirdTestStruct::<C, T>::new(),
+        }
+    }
+}
+
+fn main() {
+    use sov_modules_api::DispatchCall;
+    type RT = Runtime<u32, DefaultContext>;
+    let runtime = &mut RT::new();
+
+    let storage = ProverStorage::temporary();
+    let mut working_set = &mut sov_state::WorkingSet::new(storage);
+    let config: GenesisConfig<u32, DefaultContext> = GenesisConfig::new((), ());
+    runtime.genesis(&config, working_set).unwrap();
+    let context = DefaultContext::new(Address::try_from([0; 32].as_ref()).unwrap());
+    assert_eq!(runtime.third.get_state_value(&mut working_set), 0);
+
+    {
+        let serialized_message = RT::encode_first_call(11);
+        let module = RT::decode_call(&serialized_message).unwrap();
+
+        assert_eq!(runtime.module_address(&module), runtime.first.address());
+        let _ = runtime
+            .dispatch_call(module, working_set, &context)
+            .unwrap();
+    }
+    assert_eq!(runtime.first.get_state_value(&mut working_set), 11);
+
+    {
+        let serialized_message = cmd_parser::<u32>("ThirdTestStruct", "22").unwrap();
+        assert_eq!(serialized_message, RT::encode_third_call(22));
+        let module = RT::decode_call(&serialized_message).unwrap();
+
+        assert_eq!(runtime.module_address(&module), runtime.third.address());
+        let _ = runtime
+            .dispatch_call(module, working_set, &context)
+            .unwrap();
+    }
+    assert_eq!(runtime.third.get_state_value(&mut working_set), 22);
+}
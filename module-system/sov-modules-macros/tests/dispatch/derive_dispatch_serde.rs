@@ -0,0 +1,27 @@
+mod modules;
+use modules::{first_test_module, second_test_module};
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::Context;
+use sov_modules_macros::{DispatchCall, Genesis, MessageCodec};
+
+#[derive(Genesis, DispatchCall, MessageCodec)]
+#[serialization(borsh::BorshDeserialize, borsh::BorshSerialize)]
+#[serialization(serde::Serialize, serde::Deserialize)]
+struct Runtime<C: Context> {
+    pub first: first_test_module::FirstTestStruct<C>,
+    pub second: second_test_module::SecondTestStruct<C>,
+}
+
+fn main() {
+    use sov_modules_api::DispatchCall;
+    type RT = Runtime<DefaultContext>;
+
+    let serialized_message = RT::encode_second_call(22);
+    let call = RT::decode_call(&serialized_message).unwrap();
+
+    let json = serde_json::to_value(&call).unwrap();
+    assert_eq!(json, serde_json::json!({ "second": 22 }));
+
+    let decoded: RuntimeCall<DefaultContext> = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded, call);
+}
@@ -0,0 +1,119 @@
+mod modules;
+
+use modules::{first_test_module, second_test_module};
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::default_signature::private_key::DefaultPrivateKey;
+use sov_modules_api::hooks::{ApplyBlobHooks, TxHooks};
+use sov_modules_api::transaction::Transaction;
+use sov_modules_api::{Context, ModuleInfo, Spec};
+use sov_modules_macros::{ApplyBlobHooks, DefaultRuntime, TxHooks};
+use sov_state::{ProverStorage, WorkingSet};
+
+impl<C: Context> TxHooks for first_test_module::FirstTestStruct<C> {
+    type Context = C;
+
+    fn pre_dispatch_tx_hook(
+        &self,
+        _tx: Transaction<Self::Context>,
+        working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> anyhow::Result<<Self::Context as Spec>::Address> {
+        self.state_in_first_struct.set(&10, working_set);
+        Ok(self.address.clone())
+    }
+
+    fn post_dispatch_tx_hook(
+        &self,
+        _tx: &Transaction<Self::Context>,
+        working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
+        self.state_in_first_struct.set(&11, working_set);
+        Ok(())
+    }
+}
+
+impl<C: Context> ApplyBlobHooks for first_test_module::FirstTestStruct<C> {
+    type Context = C;
+    type BlobResult = u8;
+
+    fn begin_blob_hook(
+        &self,
+        _sequencer: &[u8],
+        _raw_blob: &[u8],
+        working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
+        self.state_in_first_struct.set(&20, working_set);
+        Ok(())
+    }
+
+    fn end_blob_hook(
+        &self,
+        result: Self::BlobResult,
+        working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
+        self.state_in_first_struct.set(&result, working_set);
+        Ok(())
+    }
+}
+
+impl<C: Context> ApplyBlobHooks for second_test_module::SecondTestStruct<C> {
+    type Context = C;
+    type BlobResult = u8;
+
+    fn begin_blob_hook(
+        &self,
+        _sequencer: &[u8],
+        _raw_blob: &[u8],
+        working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
+        self.state_in_second_struct.set(&21, working_set);
+        Ok(())
+    }
+
+    fn end_blob_hook(
+        &self,
+        result: Self::BlobResult,
+        working_set: &mut WorkingSet<<Self::Context as Spec>::Storage>,
+    ) -> anyhow::Result<()> {
+        self.state_in_second_struct.set(&(result + 1), working_set);
+        Ok(())
+    }
+}
+
+#[derive(DefaultRuntime, TxHooks, ApplyBlobHooks)]
+#[blob_result(u8)]
+struct Runtime<C>
+where
+    C: Context,
+{
+    #[tx_hooks]
+    #[blob_hooks]
+    pub first: first_test_module::FirstTestStruct<C>,
+    #[blob_hooks(|result: &u8| result * 2)]
+    pub second: second_test_module::SecondTestStruct<C>,
+}
+
+fn main() {
+    type C = DefaultContext;
+    let storage = ProverStorage::temporary();
+    let mut working_set = &mut WorkingSet::new(storage);
+    let runtime = &Runtime::<C>::new();
+
+    let tx = Transaction::<C>::new_signed_tx(&DefaultPrivateKey::generate(), vec![], 0);
+    let sender = runtime
+        .pre_dispatch_tx_hook(tx.clone(), working_set)
+        .unwrap();
+    assert_eq!(&sender, runtime.first.address());
+    assert_eq!(runtime.first.get_state_value(&mut working_set), 10);
+
+    runtime.post_dispatch_tx_hook(&tx, working_set).unwrap();
+    assert_eq!(runtime.first.get_state_value(&mut working_set), 11);
+
+    runtime.begin_blob_hook(&[], &[], working_set).unwrap();
+    assert_eq!(runtime.first.get_state_value(&mut working_set), 20);
+    assert_eq!(runtime.second.get_state_value(&mut working_set), 21);
+
+    runtime.end_blob_hook(30, working_set).unwrap();
+    assert_eq!(runtime.first.get_state_value(&mut working_set), 30);
+    // The blob result of the second module is mapped by the function given to `blob_hooks`.
+    assert_eq!(runtime.second.get_state_value(&mut working_set), 61);
+}
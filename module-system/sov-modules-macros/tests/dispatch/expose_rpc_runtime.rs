@@ -0,0 +1,44 @@
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::Context;
+use sov_modules_macros::{expose_rpc, rpc_gen, DefaultRuntime, ModuleInfo};
+use sov_state::{ProverStorage, WorkingSet};
+
+#[derive(ModuleInfo)]
+pub struct TestStruct<C: Context> {
+    #[address]
+    pub(crate) address: C::Address,
+}
+
+#[rpc_gen(client, server, namespace = "test")]
+impl<C: Context> TestStruct<C> {
+    #[rpc_method(name = "firstMethod")]
+    pub fn first_method(&self, _working_set: &mut WorkingSet<C::Storage>) -> u32 {
+        11
+    }
+}
+
+#[derive(ModuleInfo)]
+pub struct OtherStruct<C: Context> {
+    #[address]
+    pub(crate) address: C::Address,
+}
+
+#[expose_rpc(DefaultContext)]
+#[derive(DefaultRuntime)]
+struct Runtime<C: Context> {
+    #[rpc]
+    pub test_struct: TestStruct<C>,
+    // Modules without RPC methods are skipped.
+    pub other_struct: OtherStruct<C>,
+}
+
+fn main() {
+    let _runtime = Runtime::<DefaultContext>::new();
+
+    let storage = ProverStorage::temporary();
+    let methods = get_rpc_methods(storage);
+    let method_names: Vec<_> = methods.method_names().collect();
+
+    assert!(method_names.contains(&"test_firstMethod"));
+    assert!(method_names.contains(&"test_health"));
+}
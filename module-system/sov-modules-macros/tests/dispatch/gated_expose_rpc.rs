@@ -0,0 +1,23 @@
+mod modules;
+use modules::{first_test_module, second_test_module};
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::Context;
+use sov_modules_api::ModuleInfo;
+use sov_modules_macros::{DispatchCall, MessageCodec};
+
+// `expose_rpc` is never enabled, the `rpc` attribute is accepted by the `DispatchCall` derive.
+#[cfg_attr(any(), sov_modules_macros::expose_rpc(DefaultContext))]
+#[derive(DispatchCall, MessageCodec)]
+#[serialization(borsh::BorshDeserialize, borsh::BorshSerialize)]
+struct Runtime<C: Context> {
+    #[rpc]
+    pub first: first_test_module::FirstTestStruct<C>,
+    pub second: second_test_module::SecondTestStruct<C>,
+}
+
+fn main() {
+    let _runtime = Runtime::<DefaultContext> {
+        first: first_test_module::FirstTestStruct::new(),
+        second: second_test_module::SecondTestStruct::new(),
+    };
+}
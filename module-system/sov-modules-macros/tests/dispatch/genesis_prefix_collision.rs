@@ -0,0 +1,73 @@
+mod modules;
+
+use modules::first_test_module;
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::{Context, Genesis, Module, ModuleInfo, Prefix, Spec};
+use sov_modules_macros::{DefaultRuntime, Genesis};
+use sov_state::{ProverStorage, StateValue, WorkingSet};
+
+/// A module whose state is stored under the prefix of the `FirstTestStruct` module.
+struct CollidingModule<C: Context> {
+    address: C::Address,
+    state: StateValue<u8>,
+}
+
+impl<C: Context> ModuleInfo for CollidingModule<C> {
+    type Context = C;
+
+    fn new() -> Self {
+        Self {
+            address: C::Address::try_from([1; 32].as_ref()).unwrap(),
+            state: StateValue::new(Self::prefixes().remove(0).into()),
+        }
+    }
+
+    fn address(&self) -> &<Self::Context as Spec>::Address {
+        &self.address
+    }
+
+    fn prefixes() -> Vec<Prefix> {
+        vec![Prefix::new_module(
+            concat!(module_path!(), "::modules::first_test_module"),
+            "FirstTestStruct",
+        )]
+    }
+}
+
+impl<C: Context> Module for CollidingModule<C> {
+    type Context = C;
+    type Config = ();
+
+    fn genesis(
+        &self,
+        _config: &Self::Config,
+        working_set: &mut WorkingSet<C::Storage>,
+    ) -> Result<(), sov_modules_api::Error> {
+        self.state.set(&1, working_set);
+        Ok(())
+    }
+}
+
+#[derive(Genesis, DefaultRuntime)]
+struct Runtime<C: Context> {
+    pub first: first_test_module::FirstTestStruct<C>,
+    pub colliding: CollidingModule<C>,
+}
+
+fn main() {
+    let err = Runtime::<DefaultContext>::check_prefix_collisions().unwrap_err();
+    assert!(err.to_string().contains("overlaps"));
+    assert!(std::panic::catch_unwind(Runtime::<DefaultContext>::new).is_err());
+
+    // A runtime built without the constructor is still rejected by the genesis.
+    let storage = ProverStorage::temporary();
+    let working_set = &mut WorkingSet::new(storage);
+    let runtime = Runtime::<DefaultContext> {
+        first: ModuleInfo::new(),
+        colliding: ModuleInfo::new(),
+    };
+
+    let config = GenesisConfig::new((), ());
+    let err = runtime.genesis(&config, working_set).unwrap_err();
+    assert!(err.to_string().contains("overlaps"));
+}
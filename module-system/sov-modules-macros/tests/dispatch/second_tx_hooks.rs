@@ -0,0 +1,12 @@
+use sov_modules_api::Context;
+use sov_modules_macros::TxHooks;
+
+#[derive(TxHooks)]
+struct Runtime<C: Context> {
+    #[tx_hooks]
+    pub first: C::Address,
+    #[tx_hooks]
+    pub second: C::Address,
+}
+
+fn main() {}
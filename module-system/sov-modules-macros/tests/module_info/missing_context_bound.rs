@@ -0,0 +1,13 @@
+use sov_modules_macros::ModuleInfo;
+use sov_state::StateMap;
+
+#[derive(ModuleInfo)]
+struct TestStruct<A, B> {
+    #[address]
+    address: A,
+
+    #[state]
+    test_state: StateMap<u32, B>,
+}
+
+fn main() {}
@@ -1,5 +1,8 @@
 mod batch;
-
+#[cfg(feature = "native")]
+pub mod metrics;
+#[cfg(feature = "native")]
+mod parallel;
 mod tx_verifier;
 
 use std::marker::PhantomData;
@@ -8,6 +11,7 @@ pub use batch::Batch;
 use borsh::BorshDeserialize;
 use sov_modules_api::hooks::ApplyBlobHooks;
 use sov_modules_api::hooks::TxHooks;
+use sov_modules_api::transaction::Transaction;
 use sov_rollup_interface::stf::BatchReceipt;
 use sov_rollup_interface::stf::TransactionReceipt;
 use sov_rollup_interface::zk::traits::Zkvm;
@@ -26,9 +30,33 @@ pub struct AppTemplate<C: Context, RT, Vm> {
     pub current_storage: C::Storage,
     pub runtime: RT,
     working_set: Option<WorkingSet<C::Storage>>,
+    tx_body_policy: TxBodyPolicy,
+    parallel_executor: Option<ApplyTxs<C, RT, Vm>>,
     phantom_vm: PhantomData<Vm>,
 }
 
+/// Applies the transactions of a batch on top of a working set, see `AppTemplate::apply_txs`.
+type ApplyTxs<C, RT, Vm> = fn(
+    &AppTemplate<C, RT, Vm>,
+    Vec<(Transaction<C>, [u8; 32], Option<Vec<u8>>)>,
+    WorkingSet<<C as Spec>::Storage>,
+) -> (
+    WorkingSet<<C as Spec>::Storage>,
+    Result<Vec<TransactionReceipt<TxEffect>>, SlashingReason>,
+);
+
+/// Decides which transaction bodies are saved in the transaction receipts, and eventually in the ledger.
+/// The bodies don't affect the state root, so nodes of the same rollup are free to use different policies.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxBodyPolicy {
+    /// Don't save any transaction bodies.
+    #[default]
+    Never,
+    /// Save the bodies of all the transactions which were included in the ledger, including the reverted ones.
+    Always,
+}
+
 impl<C: Context, RT, Vm> AppTemplate<C, RT, Vm>
 where
     RT: DispatchCall<Context = C>
@@ -41,10 +69,18 @@ where
             runtime,
             current_storage: storage,
             working_set: None,
+            tx_body_policy: TxBodyPolicy::default(),
+            parallel_executor: None,
             phantom_vm: PhantomData,
         }
     }
 
+    /// Sets the policy deciding which transaction bodies are saved in the receipts. Defaults to [`TxBodyPolicy::Never`].
+    pub fn with_tx_body_policy(mut self, tx_body_policy: TxBodyPolicy) -> Self {
+        self.tx_body_policy = tx_body_policy;
+        self
+    }
+
     // TODO: implement a state machine instead of manually deciding when to commit and when to revert
     pub fn apply_batch(
         &mut self,
@@ -105,7 +141,10 @@ where
         debug!("Deserialized batch with {} txs", batch.txs.len());
 
         // Run the stateless verification, since it is stateless we don't commit.
-        let txs = match verify_txs_stateless(batch.take_transactions()) {
+        let txs = match verify_txs_stateless(
+            batch.take_transactions(),
+            self.tx_body_policy == TxBodyPolicy::Always,
+        ) {
             Ok(txs) => txs,
             Err(e) => {
                 // Revert on error
@@ -120,80 +159,22 @@ where
             }
         };
 
-        let mut tx_receipts = Vec::with_capacity(txs.len());
-
-        // Process transactions in a loop, commit changes after every step of the loop.
-        for (tx, raw_tx_hash) in txs {
-            batch_workspace = batch_workspace.to_revertable();
-
-            // Run the stateful verification, possibly modifies the state.
-            let sender_address = match self
-                .runtime
-                .pre_dispatch_tx_hook(tx.clone(), &mut batch_workspace)
-            {
-                Ok(verified_tx) => verified_tx,
-                Err(e) => {
-                    // Don't revert any state changes made by the pre_dispatch_hook even if it rejects
-                    error!("Stateful verification error - the sequencer included an invalid transaction: {}", e);
-                    batch_workspace = batch_workspace.revert();
-                    let receipt = TransactionReceipt {
-                        tx_hash: raw_tx_hash,
-                        body_to_save: None,
-                        events: batch_workspace.take_events(),
-                        receipt: TxEffect::Reverted,
-                    };
-
-                    tx_receipts.push(receipt);
-                    continue;
-                }
-            };
-
-            match RT::decode_call(tx.runtime_msg()) {
-                Ok(msg) => {
-                    let ctx = C::new(sender_address.clone());
-                    let tx_result = self.runtime.dispatch_call(msg, &mut batch_workspace, &ctx);
-
-                    self.runtime
-                        .post_dispatch_tx_hook(&tx, &mut batch_workspace)
-                        .expect("Impossible happened: error in post_dispatch_tx_hook");
-
-                    let tx_effect = match tx_result {
-                        Ok(_) => TxEffect::Successful,
-                        Err(_e) => {
-                            // The transaction causing invalid state transition is reverted but we don't slash and we continue
-                            // processing remaining transactions.
-                            batch_workspace = batch_workspace.revert();
-                            TxEffect::Reverted
-                        }
-                    };
-
-                    let receipt = TransactionReceipt {
-                        tx_hash: raw_tx_hash,
-                        body_to_save: None,
-                        events: batch_workspace.take_events(),
-                        receipt: tx_effect,
-                    };
-
-                    tx_receipts.push(receipt);
-                }
-                Err(e) => {
-                    // If the serialization is invalid, the sequencer is malicious. Slash them (we don't run exit_apply_batch here)
-                    let batch_workspace = batch_workspace.revert();
-                    self.working_set = Some(batch_workspace);
-                    error!("Tx 0x{} decoding error: {}", hex::encode(raw_tx_hash), e);
-                    return BatchReceipt {
-                        batch_hash: batch_data_and_hash.hash,
-                        tx_receipts: Vec::new(),
-                        inner: SequencerOutcome::Slashed(
-                            SlashingReason::InvalidTransactionEncoding,
-                        ),
-                    };
-                }
+        let (workspace, tx_receipts) = match self.parallel_executor {
+            Some(apply_txs) => apply_txs(self, txs, batch_workspace),
+            None => self.apply_txs(txs, batch_workspace),
+        };
+        batch_workspace = workspace;
+        let tx_receipts = match tx_receipts {
+            Ok(tx_receipts) => tx_receipts,
+            Err(slashing_reason) => {
+                self.working_set = Some(batch_workspace);
+                return BatchReceipt {
+                    batch_hash: batch_data_and_hash.hash,
+                    tx_receipts: Vec::new(),
+                    inner: SequencerOutcome::Slashed(slashing_reason),
+                };
             }
-
-            // commit each step of the loop
-            batch_workspace = batch_workspace.commit();
-        }
+        };
 
         // TODO: calculate the amount based of gas and fees
 
@@ -209,6 +190,107 @@ where
             inner: batch_receipt_contents,
         }
     }
+
+    /// Applies the transactions of a batch one after the other. Fails if the sequencer has to be slashed.
+    fn apply_txs(
+        &self,
+        txs: Vec<(Transaction<C>, [u8; 32], Option<Vec<u8>>)>,
+        mut batch_workspace: WorkingSet<C::Storage>,
+    ) -> (
+        WorkingSet<C::Storage>,
+        Result<Vec<TransactionReceipt<TxEffect>>, SlashingReason>,
+    ) {
+        let mut tx_receipts = Vec::with_capacity(txs.len());
+        for (tx, raw_tx_hash, body_to_save) in txs {
+            let (workspace, receipt) = Self::apply_tx(
+                &self.runtime,
+                tx,
+                raw_tx_hash,
+                body_to_save,
+                batch_workspace,
+            );
+            batch_workspace = workspace;
+            match receipt {
+                Ok(receipt) => tx_receipts.push(receipt),
+                Err(slashing_reason) => return (batch_workspace, Err(slashing_reason)),
+            }
+        }
+        (batch_workspace, Ok(tx_receipts))
+    }
+
+    /// Applies a single transaction on top of `batch_workspace`, and commits its changes unless it is reverted.
+    /// Fails if the sequencer has to be slashed, in which case the changes of the transaction are reverted.
+    /// `body_to_save` is the serialized transaction if the [`TxBodyPolicy`] saves it in the receipt.
+    fn apply_tx(
+        runtime: &RT,
+        tx: Transaction<C>,
+        raw_tx_hash: [u8; 32],
+        body_to_save: Option<Vec<u8>>,
+        batch_workspace: WorkingSet<C::Storage>,
+    ) -> (
+        WorkingSet<C::Storage>,
+        Result<TransactionReceipt<TxEffect>, SlashingReason>,
+    ) {
+        let mut batch_workspace = batch_workspace.to_revertable();
+
+        // Run the stateful verification, possibly modifies the state.
+        let sender_address = match runtime.pre_dispatch_tx_hook(tx.clone(), &mut batch_workspace) {
+            Ok(verified_tx) => verified_tx,
+            Err(e) => {
+                // Don't revert any state changes made by the pre_dispatch_hook even if it rejects
+                error!("Stateful verification error - the sequencer included an invalid transaction: {}", e);
+                let mut batch_workspace = batch_workspace.revert();
+                let receipt = TransactionReceipt {
+                    tx_hash: raw_tx_hash,
+                    body_to_save,
+                    events: batch_workspace.take_events(),
+                    receipt: TxEffect::Reverted,
+                };
+                return (batch_workspace, Ok(receipt));
+            }
+        };
+
+        match RT::decode_call(tx.runtime_msg()) {
+            Ok(msg) => {
+                let ctx = C::new(sender_address).with_nonce_lane(tx.nonce_lane());
+                let tx_result = runtime
+                    .pre_dispatch_call_hook(&tx, runtime.module_address(&msg), &mut batch_workspace)
+                    .map_err(sov_modules_api::Error::from)
+                    .and_then(|_| runtime.dispatch_call(msg, &mut batch_workspace, &ctx));
+
+                runtime
+                    .post_dispatch_tx_hook(&tx, &mut batch_workspace)
+                    .expect("Impossible happened: error in post_dispatch_tx_hook");
+
+                let tx_effect = match tx_result {
+                    Ok(_) => TxEffect::Successful,
+                    Err(_e) => {
+                        // The transaction causing invalid state transition is reverted but we don't slash and we continue
+                        // processing remaining transactions.
+                        batch_workspace = batch_workspace.revert();
+                        TxEffect::Reverted
+                    }
+                };
+
+                let receipt = TransactionReceipt {
+                    tx_hash: raw_tx_hash,
+                    body_to_save,
+                    events: batch_workspace.take_events(),
+                    receipt: tx_effect,
+                };
+
+                (batch_workspace.commit(), Ok(receipt))
+            }
+            Err(e) => {
+                // If the serialization is invalid, the sequencer is malicious. Slash them (we don't run exit_apply_batch here)
+                error!("Tx 0x{} decoding error: {}", hex::encode(raw_tx_hash), e);
+                (
+                    batch_workspace.revert(),
+                    Err(SlashingReason::InvalidTransactionEncoding),
+                )
+            }
+        }
+    }
 }
 
 struct BatchDataAndHash {
@@ -298,15 +380,22 @@ where
         let sequencer = blob.sender();
         let sequencer = sequencer.as_ref();
 
-        self.apply_batch(sequencer, blob.data())
+        let receipt = self.apply_batch(sequencer, blob.data());
+        #[cfg(feature = "native")]
+        metrics::record_batch(&receipt);
+        receipt
     }
 
     fn end_slot(&mut self) -> (Self::StateRoot, Self::Witness) {
         let (cache_log, witness) = self.working_set.take().unwrap().freeze();
+        #[cfg(feature = "native")]
+        let timer = metrics::STF_JMT_COMMIT_LATENCY_SECONDS.start_timer();
         let root_hash = self
             .current_storage
             .validate_and_commit(cache_log, &witness)
             .expect("jellyfish merkle tree update must succeed");
+        #[cfg(feature = "native")]
+        timer.observe_duration();
         (jmt::RootHash(root_hash), witness)
     }
 }
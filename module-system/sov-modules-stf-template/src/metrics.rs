@@ -0,0 +1,70 @@
+use once_cell::sync::Lazy;
+use prometheus::{
+    exponential_buckets, register_histogram, register_int_counter, register_int_counter_vec,
+    Histogram, IntCounter, IntCounterVec,
+};
+use sov_rollup_interface::stf::BatchReceipt;
+
+use crate::{SequencerOutcome, TxEffect};
+
+pub static STF_TXS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "sov_stf_txs",
+        // metric description
+        "Transactions included in the applied batches, by effect",
+        // metric labels (dimensions)
+        &["effect"]
+    )
+    .unwrap()
+});
+
+pub static STF_BATCHES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "sov_stf_batches",
+        // metric description
+        "Applied batches, by sequencer outcome",
+        // metric labels (dimensions)
+        &["outcome"]
+    )
+    .unwrap()
+});
+
+pub static STF_JMT_COMMIT_LATENCY_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "sov_stf_jmt_commit_latency_seconds",
+        // metric description
+        "Latency of the jellyfish merkle tree commit at the end of each slot, in seconds",
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static STF_REEXECUTED_TXS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        // metric name
+        "sov_stf_reexecuted_txs",
+        // metric description
+        "Transactions executed again after a conflict with an earlier transaction of their batch, when executing in parallel"
+    )
+    .unwrap()
+});
+
+/// Records the outcome of an applied batch and the effects of its transactions.
+pub(crate) fn record_batch(receipt: &BatchReceipt<SequencerOutcome, TxEffect>) {
+    let outcome = match receipt.inner {
+        SequencerOutcome::Rewarded(_) => "rewarded",
+        SequencerOutcome::Slashed(_) => "slashed",
+        SequencerOutcome::Ignored => "ignored",
+    };
+    STF_BATCHES.with_label_values(&[outcome]).inc();
+    for tx_receipt in &receipt.tx_receipts {
+        let effect = match tx_receipt.receipt {
+            TxEffect::Successful => "successful",
+            TxEffect::Reverted => "reverted",
+        };
+        STF_TXS.with_label_values(&[effect]).inc();
+    }
+}
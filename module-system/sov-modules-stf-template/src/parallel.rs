@@ -0,0 +1,93 @@
+use rayon::prelude::*;
+use sov_modules_api::hooks::{ApplyBlobHooks, TxHooks};
+use sov_modules_api::transaction::Transaction;
+use sov_modules_api::{Context, DispatchCall, Genesis};
+use sov_rollup_interface::stf::TransactionReceipt;
+use sov_state::WorkingSet;
+use tracing::debug;
+
+use crate::{metrics, AppTemplate, SequencerOutcome, SlashingReason, TxEffect};
+
+impl<C: Context, RT, Vm> AppTemplate<C, RT, Vm>
+where
+    RT: DispatchCall<Context = C>
+        + Genesis<Context = C>
+        + TxHooks<Context = C>
+        + ApplyBlobHooks<Context = C, BlobResult = SequencerOutcome>
+        + Sync,
+    C::Storage: Send + Sync,
+    Transaction<C>: Sync,
+{
+    /// Executes the transactions of each batch optimistically in parallel, on the rayon thread pool.
+    /// The state roots, receipts and witnesses are the same as with the sequential execution.
+    pub fn with_parallel_execution(mut self) -> Self {
+        self.parallel_executor = Some(Self::apply_txs_in_parallel);
+        self
+    }
+
+    /// Executes all the transactions speculatively in parallel, each one on its own working set forked from the
+    /// state at the beginning of the batch. Then, in the order of the batch, replays the reads and writes of each
+    /// transaction on top of the batch working set. A transaction which read a value written by an earlier transaction
+    /// of the batch conflicts with it, and is executed again on the batch working set.
+    fn apply_txs_in_parallel(
+        &self,
+        txs: Vec<(Transaction<C>, [u8; 32], Option<Vec<u8>>)>,
+        mut batch_workspace: WorkingSet<C::Storage>,
+    ) -> (
+        WorkingSet<C::Storage>,
+        Result<Vec<TransactionReceipt<TxEffect>>, SlashingReason>,
+    ) {
+        let snapshot = batch_workspace.snapshot();
+        let speculative_results: Vec<_> = txs
+            .par_iter()
+            .map(|(tx, raw_tx_hash, body_to_save)| {
+                let (mut workspace, receipt) = Self::apply_tx(
+                    &self.runtime,
+                    tx.clone(),
+                    *raw_tx_hash,
+                    body_to_save.clone(),
+                    snapshot.fork(),
+                );
+                // The witness of a speculative working set is empty, the reads are recorded when they are replayed.
+                let (accesses, _) = workspace.freeze();
+                (accesses, receipt)
+            })
+            .collect();
+
+        let mut tx_receipts = Vec::with_capacity(txs.len());
+        for ((tx, raw_tx_hash, body_to_save), (accesses, receipt)) in
+            txs.into_iter().zip(speculative_results)
+        {
+            let mut workspace = batch_workspace.to_revertable();
+            let receipt = if workspace.replay(accesses) {
+                // Slashing reverts the changes of the transaction, like in `apply_tx`.
+                batch_workspace = match receipt {
+                    Ok(_) => workspace.commit(),
+                    Err(_) => workspace.revert(),
+                };
+                receipt
+            } else {
+                debug!(
+                    "Tx 0x{} conflicts with an earlier tx of the batch, executing it again",
+                    hex::encode(raw_tx_hash)
+                );
+                metrics::STF_REEXECUTED_TXS.inc();
+                let (workspace, receipt) = Self::apply_tx(
+                    &self.runtime,
+                    tx,
+                    raw_tx_hash,
+                    body_to_save,
+                    workspace.revert(),
+                );
+                batch_workspace = workspace;
+                receipt
+            };
+
+            match receipt {
+                Ok(receipt) => tx_receipts.push(receipt),
+                Err(slashing_reason) => return (batch_workspace, Err(slashing_reason)),
+            }
+        }
+        (batch_workspace, Ok(tx_receipts))
+    }
+}
@@ -17,9 +17,12 @@ impl RawTx {
 
 type RawTxHash = [u8; 32];
 
+/// Deserializes the transactions and verifies their signatures. The serialized body of each transaction
+/// is only returned if `keep_bodies` is set, otherwise it is dropped as soon as the transaction is decoded.
 pub fn verify_txs_stateless<C: Context>(
     raw_txs: Vec<RawTx>,
-) -> anyhow::Result<Vec<(Transaction<C>, RawTxHash)>> {
+    keep_bodies: bool,
+) -> anyhow::Result<Vec<(Transaction<C>, RawTxHash, Option<Vec<u8>>)>> {
     let mut txs = Vec::with_capacity(raw_txs.len());
     debug!("Verifying {} transactions", raw_txs.len());
     for raw_tx in raw_txs {
@@ -27,7 +30,8 @@ pub fn verify_txs_stateless<C: Context>(
         let mut data = Cursor::new(&raw_tx.data);
         let tx = Transaction::<C>::deserialize_reader(&mut data)?;
         tx.verify()?;
-        txs.push((tx, raw_tx_hash));
+        let body = keep_bodies.then_some(raw_tx.data);
+        txs.push((tx, raw_tx_hash, body));
     }
     Ok(txs)
 }
@@ -4,4 +4,8 @@ use std::path::PathBuf;
 pub struct Config {
     /// Path to folder where storage files will be stored
     pub path: PathBuf,
+    /// The tuning of the rocksdb instance of the storage. Defaults to [`sov_db::rocks_db_config::RocksdbConfig::default`].
+    #[cfg(feature = "native")]
+    #[serde(default)]
+    pub rocksdb: sov_db::rocks_db_config::RocksdbConfig,
 }
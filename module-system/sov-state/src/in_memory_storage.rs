@@ -0,0 +1,357 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+use jmt::storage::{LeafNode, Node, NodeBatch, NodeKey, TreeReader, TreeWriter};
+use jmt::{JellyfishMerkleTree, KeyHash, OwnedValue, PhantomHasher, SimpleHasher, Version};
+
+use crate::tree_db::TreeReadLogger;
+use crate::witness::Witness;
+use crate::{
+    internal_cache::OrderedReadsAndWrites,
+    storage::{StorageKey, StorageValue},
+    MerkleProofSpec, Storage,
+};
+
+/// A [`Storage`] which keeps the jellyfish merkle tree in memory. It computes the same state roots and
+/// witnesses as the `ProverStorage`, without touching the disk, so it is meant for tests and fuzzing.
+/// Clones share the same tree.
+pub struct InMemoryStorage<S: MerkleProofSpec> {
+    db: InMemoryTreeDb,
+    _phantom_hasher: PhantomHasher<S::Hasher>,
+}
+
+impl<S: MerkleProofSpec> Clone for InMemoryStorage<S> {
+    fn clone(&self) -> Self {
+        Self {
+            db: self.db.clone(),
+            _phantom_hasher: Default::default(),
+        }
+    }
+}
+
+impl<S: MerkleProofSpec> Default for InMemoryStorage<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: MerkleProofSpec> InMemoryStorage<S> {
+    /// Creates an empty storage.
+    pub fn new() -> Self {
+        Self {
+            db: InMemoryTreeDb::default(),
+            _phantom_hasher: Default::default(),
+        }
+    }
+
+    /// Returns the latest version committed to the storage, `0` if the storage is empty.
+    pub fn latest_version(&self) -> u64 {
+        self.db.next_version() - 1
+    }
+
+    /// Returns the root hash of the state at the given version.
+    pub fn get_root_hash(&self, version: u64) -> Result<[u8; 32], anyhow::Error> {
+        let jmt = JellyfishMerkleTree::<_, S::Hasher>::new(&self.db);
+        Ok(jmt.get_root_hash(version)?.0)
+    }
+
+    fn key_hash(key: &StorageKey) -> KeyHash {
+        KeyHash(S::Hasher::hash(key.key.as_ref()))
+    }
+}
+
+impl<S: MerkleProofSpec> Storage for InMemoryStorage<S> {
+    type Witness = S::Witness;
+    type RuntimeConfig = ();
+
+    fn with_config(_config: Self::RuntimeConfig) -> Result<Self, anyhow::Error> {
+        Ok(Self::new())
+    }
+
+    fn get(&self, key: StorageKey, witness: &Self::Witness) -> Option<StorageValue> {
+        let val = self
+            .db
+            .get_value_option(self.db.next_version(), Self::key_hash(&key))
+            .expect("Reading from memory can't fail")
+            .map(StorageValue::new_from_bytes);
+        witness.add_hint(val.clone());
+        val
+    }
+
+    // Mirrors `ProverStorage::validate_and_commit`, so that both storages produce the same witness.
+    fn validate_and_commit(
+        &self,
+        state_accesses: OrderedReadsAndWrites,
+        witness: &Self::Witness,
+    ) -> Result<[u8; 32], anyhow::Error> {
+        let latest_version = self.db.next_version() - 1;
+        witness.add_hint(latest_version);
+
+        let read_logger = TreeReadLogger::with_db_and_witness(self.db.clone(), witness);
+        let untracked_jmt = JellyfishMerkleTree::<_, S::Hasher>::new(&self.db);
+
+        // Handle empty untracked_jmt
+        if untracked_jmt
+            .get_root_hash_option(latest_version)?
+            .is_none()
+        {
+            assert_eq!(latest_version, 0);
+            let empty_batch = Vec::default().into_iter();
+            let (_, tree_update) = untracked_jmt
+                .put_value_set(empty_batch, latest_version)
+                .expect("JMT update must succeed");
+            self.db.write_node_batch(&tree_update.node_batch)?;
+        }
+
+        // For each value that's been read from the tree, read it from the logged JMT to populate hints
+        for (key, read_value) in state_accesses.ordered_reads {
+            let (result, proof) =
+                untracked_jmt.get_with_proof(Self::key_hash(&key), latest_version)?;
+            if result.as_ref() != read_value.as_ref().map(|f| f.value.as_ref()) {
+                anyhow::bail!("Bug! Incorrect value read from jmt");
+            }
+            witness.add_hint(proof);
+        }
+
+        let tracked_jmt = JellyfishMerkleTree::<_, S::Hasher>::new(&read_logger);
+        // Compute the jmt update from the write batch
+        let batch = state_accesses
+            .ordered_writes
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    Self::key_hash(&key),
+                    value.map(|v| Arc::try_unwrap(v.value).unwrap_or_else(|arc| (*arc).clone())),
+                )
+            });
+
+        let (new_root, tree_update) = tracked_jmt
+            .put_value_set(batch, latest_version + 1)
+            .expect("JMT update must succeed");
+
+        self.db.write_node_batch(&tree_update.node_batch)?;
+        self.db.inc_next_version();
+        Ok(new_root.0)
+    }
+
+    // Based on assumption `validate_and_commit` increments version.
+    fn is_empty(&self) -> bool {
+        self.db.next_version() <= 1
+    }
+}
+
+/// The nodes and values of a jellyfish merkle tree, shared between clones.
+#[derive(Clone)]
+struct InMemoryTreeDb {
+    inner: Arc<RwLock<TreeData>>,
+}
+
+struct TreeData {
+    nodes: HashMap<NodeKey, Node>,
+    /// The values by key hash and version, so that the latest version of a key is the last one before `(key_hash, version)`.
+    values: BTreeMap<([u8; 32], Version), Option<OwnedValue>>,
+    next_version: Version,
+}
+
+impl Default for InMemoryTreeDb {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(TreeData {
+                nodes: HashMap::new(),
+                values: BTreeMap::new(),
+                // Like the `StateDB`, the first version written is 1
+                next_version: 1,
+            })),
+        }
+    }
+}
+
+impl InMemoryTreeDb {
+    fn next_version(&self) -> Version {
+        self.inner.read().unwrap().next_version
+    }
+
+    fn inc_next_version(&self) {
+        self.inner.write().unwrap().next_version += 1;
+    }
+}
+
+impl TreeReader for InMemoryTreeDb {
+    fn get_node_option(&self, node_key: &NodeKey) -> anyhow::Result<Option<Node>> {
+        Ok(self.inner.read().unwrap().nodes.get(node_key).cloned())
+    }
+
+    fn get_value_option(
+        &self,
+        max_version: Version,
+        key_hash: KeyHash,
+    ) -> anyhow::Result<Option<OwnedValue>> {
+        let data = self.inner.read().unwrap();
+        Ok(data
+            .values
+            .range((key_hash.0, 0)..=(key_hash.0, max_version))
+            .next_back()
+            .and_then(|(_, value)| value.clone()))
+    }
+
+    fn get_rightmost_leaf(&self) -> anyhow::Result<Option<(NodeKey, LeafNode)>> {
+        let data = self.inner.read().unwrap();
+        let latest_version = data.next_version - 1;
+        // The nodes of the previous versions are kept, so the leaves of deleted keys are skipped. The other stale
+        // leaves of a key are older than its leaf in the latest tree, which is the last one written.
+        let mut rightmost: Option<(NodeKey, LeafNode)> = None;
+        for (node_key, node) in data.nodes.iter() {
+            let Node::Leaf(leaf) = node else {
+                continue;
+            };
+            let is_deleted = data
+                .values
+                .range((leaf.key_hash().0, 0)..=(leaf.key_hash().0, latest_version))
+                .next_back()
+                .map_or(true, |(_, value)| value.is_none());
+            if is_deleted {
+                continue;
+            }
+            if rightmost
+                .as_ref()
+                .map_or(true, |(rightmost_key, rightmost_leaf)| {
+                    (leaf.key_hash(), node_key.version())
+                        > (rightmost_leaf.key_hash(), rightmost_key.version())
+                })
+            {
+                rightmost = Some((node_key.clone(), leaf.clone()));
+            }
+        }
+        Ok(rightmost)
+    }
+}
+
+impl TreeWriter for InMemoryTreeDb {
+    fn write_node_batch(&self, node_batch: &NodeBatch) -> anyhow::Result<()> {
+        let mut data = self.inner.write().unwrap();
+        for (node_key, node) in node_batch.nodes() {
+            data.nodes.insert(node_key.clone(), node.clone());
+        }
+        for ((version, key_hash), value) in node_batch.values() {
+            data.values.insert((key_hash.0, *version), value.clone());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "native"))]
+mod tests {
+    use super::*;
+    use crate::{DefaultStorageSpec, ProverStorage, WorkingSet, ZkStorage};
+
+    fn commit<S: Storage>(
+        storage: &S,
+        writes: &[(&'static str, Option<&'static str>)],
+    ) -> [u8; 32] {
+        let mut working_set = WorkingSet::new(storage.clone());
+        for (key, value) in writes {
+            match value {
+                Some(value) => working_set.set(StorageKey::from(*key), StorageValue::from(*value)),
+                None => working_set.delete(StorageKey::from(*key)),
+            }
+        }
+        let (cache, witness) = working_set.freeze();
+        storage
+            .validate_and_commit(cache, &witness)
+            .expect("storage is valid")
+    }
+
+    #[test]
+    fn test_same_roots_as_prover_storage() {
+        let in_memory_storage = InMemoryStorage::<DefaultStorageSpec>::new();
+        let prover_storage = ProverStorage::<DefaultStorageSpec>::temporary();
+        assert!(in_memory_storage.is_empty());
+
+        let slots: [&[(&str, Option<&str>)]; 3] = [
+            &[("key_0", Some("value_0")), ("key_1", Some("value_1"))],
+            &[("key_1", Some("value_2")), ("key_2", Some("value_3"))],
+            &[("key_0", None)],
+        ];
+        for writes in slots {
+            assert_eq!(
+                commit(&in_memory_storage, writes),
+                commit(&prover_storage, writes)
+            );
+        }
+        assert_eq!(in_memory_storage.latest_version(), 3);
+        assert_eq!(
+            in_memory_storage.get_root_hash(2).unwrap(),
+            prover_storage.get_root_hash(2).unwrap()
+        );
+
+        let witness = Default::default();
+        assert_eq!(
+            in_memory_storage.get(StorageKey::from("key_0"), &witness),
+            None
+        );
+        assert_eq!(
+            in_memory_storage.get(StorageKey::from("key_1"), &witness),
+            Some(StorageValue::from("value_2"))
+        );
+    }
+
+    #[test]
+    fn test_witness_verifies_in_zk() {
+        let storage = InMemoryStorage::<DefaultStorageSpec>::new();
+        let prev_root = commit(&storage, &[("key_0", Some("value_0"))]);
+
+        let mut working_set = WorkingSet::new(storage.clone());
+        assert_eq!(
+            working_set.get(StorageKey::from("key_0")),
+            Some(StorageValue::from("value_0"))
+        );
+        working_set.set(StorageKey::from("key_1"), StorageValue::from("value_1"));
+        let (cache, witness) = working_set.freeze();
+        let root = storage.validate_and_commit(cache, &witness).unwrap();
+
+        let mut zk_working_set =
+            WorkingSet::with_witness(ZkStorage::<DefaultStorageSpec>::new(prev_root), witness);
+        assert_eq!(
+            zk_working_set.get(StorageKey::from("key_0")),
+            Some(StorageValue::from("value_0"))
+        );
+        zk_working_set.set(StorageKey::from("key_1"), StorageValue::from("value_1"));
+        let (cache, witness) = zk_working_set.freeze();
+        let zk_storage = ZkStorage::<DefaultStorageSpec>::new(prev_root);
+        assert_eq!(
+            zk_storage.validate_and_commit(cache, &witness).unwrap(),
+            root
+        );
+    }
+
+    #[test]
+    fn test_rightmost_leaf_skips_deleted_keys() {
+        let storage = InMemoryStorage::<DefaultStorageSpec>::new();
+        let keys = ["key_0", "key_1", "key_2"];
+        commit(
+            &storage,
+            &[
+                (keys[0], Some("value_0")),
+                (keys[1], Some("value_1")),
+                (keys[2], Some("value_2")),
+            ],
+        );
+
+        let key_hash =
+            |key: &str| InMemoryStorage::<DefaultStorageSpec>::key_hash(&StorageKey::from(key));
+        let rightmost_key_hash = |storage: &InMemoryStorage<DefaultStorageSpec>| {
+            let (_, leaf) = storage.db.get_rightmost_leaf().unwrap().unwrap();
+            leaf.key_hash()
+        };
+
+        let mut sorted_keys = keys;
+        sorted_keys.sort_by_key(|key| key_hash(key));
+        assert_eq!(rightmost_key_hash(&storage), key_hash(sorted_keys[2]));
+
+        commit(&storage, &[(sorted_keys[2], None)]);
+        assert_eq!(rightmost_key_hash(&storage), key_hash(sorted_keys[1]));
+
+        commit(&storage, &[(sorted_keys[0], None), (sorted_keys[1], None)]);
+        assert!(storage.db.get_rightmost_leaf().unwrap().is_none());
+    }
+}
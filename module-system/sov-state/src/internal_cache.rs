@@ -48,6 +48,15 @@ impl StorageInternalCache {
         key: StorageKey,
         value_reader: &S,
         witness: &S::Witness,
+    ) -> Option<StorageValue> {
+        self.get_or_fetch_with(key, |key| value_reader.get(key, witness))
+    }
+
+    /// Gets a value from the cache or reads it with `fetch`. The values fetched are recorded as reads.
+    pub(crate) fn get_or_fetch_with(
+        &mut self,
+        key: StorageKey,
+        fetch: impl FnOnce(StorageKey) -> Option<StorageValue>,
     ) -> Option<StorageValue> {
         let cache_key = key.clone().as_cache_key();
         let cache_value = self.get_value_from_cache(cache_key.clone());
@@ -58,7 +67,7 @@ impl StorageInternalCache {
             }
             // If the value does not exist in the cache, then fetch it from an external source.
             cache::ValueExists::No => {
-                let storage_value = value_reader.get(key, witness);
+                let storage_value = fetch(key);
                 let cache_value = storage_value.as_ref().map(|v| v.clone().as_cache_value());
 
                 self.add_read(cache_key, cache_value);
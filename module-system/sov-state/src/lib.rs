@@ -1,3 +1,5 @@
+#[cfg(feature = "native")]
+mod in_memory_storage;
 mod internal_cache;
 mod map;
 #[cfg(feature = "native")]
@@ -16,6 +18,9 @@ pub mod config;
 mod state_tests;
 
 pub use crate::witness::{ArrayWitness, TreeWitnessReader, Witness};
+#[cfg(feature = "native")]
+pub use in_memory_storage::InMemoryStorage;
+pub use internal_cache::OrderedReadsAndWrites;
 pub use map::StateMap;
 #[cfg(feature = "native")]
 pub use prover_storage::{delete_storage, ProverStorage};
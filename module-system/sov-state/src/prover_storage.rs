@@ -9,6 +9,7 @@ use crate::{
     MerkleProofSpec, Storage,
 };
 use jmt::{storage::TreeWriter, JellyfishMerkleTree, KeyHash, PhantomHasher, SimpleHasher};
+use sov_db::rocks_db_config::RocksdbConfig;
 use sov_db::state_db::StateDB;
 
 pub struct ProverStorage<S: MerkleProofSpec> {
@@ -37,6 +38,22 @@ impl<S: MerkleProofSpec> ProverStorage<S> {
         Self::with_db(db)
     }
 
+    /// Opens the storage at `primary_path` as a read-only secondary, see [`StateDB::secondary_with_path`].
+    /// Used to serve queries from a different process than the one executing the rollup.
+    pub fn secondary_with_path(
+        primary_path: impl AsRef<Path>,
+        secondary_path: impl AsRef<Path>,
+        rocksdb_config: &RocksdbConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let db = StateDB::secondary_with_path(primary_path, secondary_path, rocksdb_config)?;
+        Self::with_db(db)
+    }
+
+    /// Catches a secondary storage up with the versions committed by the primary.
+    pub fn catch_up_with_primary(&self) -> Result<(), anyhow::Error> {
+        self.db.catch_up_with_primary()
+    }
+
     fn with_db(db: StateDB) -> Result<Self, anyhow::Error> {
         Ok(Self {
             db,
@@ -44,6 +61,23 @@ impl<S: MerkleProofSpec> ProverStorage<S> {
         })
     }
 
+    /// Returns the latest version committed to the storage, `0` if the storage is empty.
+    pub fn latest_version(&self) -> u64 {
+        self.db.get_latest_version().unwrap_or_default()
+    }
+
+    /// Discards all the versions committed after `version`. Used on startup to bring the storage
+    /// back to the last slot committed to the ledger, see `LedgerDB::get_last_state_version`.
+    pub fn rollback_to(&self, version: u64) -> Result<(), anyhow::Error> {
+        self.db.rollback_to(version)
+    }
+
+    /// Returns the root hash of the state at the given version.
+    pub fn get_root_hash(&self, version: u64) -> Result<[u8; 32], anyhow::Error> {
+        let jmt = JellyfishMerkleTree::<_, S::Hasher>::new(&self.db);
+        Ok(jmt.get_root_hash(version)?.0)
+    }
+
     fn read_value(&self, key: StorageKey) -> Option<StorageValue> {
         match self
             .db
@@ -61,7 +95,8 @@ impl<S: MerkleProofSpec> Storage for ProverStorage<S> {
     type RuntimeConfig = Config;
 
     fn with_config(config: Self::RuntimeConfig) -> Result<Self, anyhow::Error> {
-        Self::with_path(config.path.as_path())
+        let db = StateDB::with_config(&config.path, &config.rocksdb)?;
+        Self::with_db(db)
     }
 
     fn get(&self, key: StorageKey, witness: &Self::Witness) -> Option<StorageValue> {
@@ -217,6 +252,52 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_rollback() {
+        let path = sov_schema_db::temppath::TempPath::new();
+        let tests = create_tests();
+        {
+            let prover_storage = ProverStorage::<DefaultStorageSpec>::with_path(&path).unwrap();
+            for test in tests.clone() {
+                let mut storage = WorkingSet::new(prover_storage.clone());
+                storage.set(test.key.clone(), test.value.clone());
+                let (cache, witness) = storage.freeze();
+                prover_storage
+                    .validate_and_commit(cache, &witness)
+                    .expect("storage is valid");
+            }
+            assert_eq!(prover_storage.latest_version(), 3);
+
+            let root_hash = prover_storage.get_root_hash(1).unwrap();
+            prover_storage.rollback_to(1).unwrap();
+            assert_eq!(prover_storage.latest_version(), 1);
+            assert_eq!(prover_storage.get_root_hash(1).unwrap(), root_hash);
+        }
+
+        // The rollback survives a restart, and the storage can be written again.
+        let prover_storage = ProverStorage::<DefaultStorageSpec>::with_path(&path).unwrap();
+        assert_eq!(prover_storage.latest_version(), 1);
+        let witness = Default::default();
+        assert_eq!(
+            prover_storage.get(tests[0].key.clone(), &witness),
+            Some(tests[0].value.clone())
+        );
+        assert_eq!(prover_storage.get(tests[1].key.clone(), &witness), None);
+        assert_eq!(prover_storage.get(tests[2].key.clone(), &witness), None);
+
+        let mut storage = WorkingSet::new(prover_storage.clone());
+        storage.set(tests[2].key.clone(), tests[0].value.clone());
+        let (cache, witness) = storage.freeze();
+        prover_storage
+            .validate_and_commit(cache, &witness)
+            .expect("storage is valid");
+        assert_eq!(prover_storage.latest_version(), 2);
+        assert_eq!(
+            prover_storage.get(tests[2].key.clone(), &witness),
+            Some(tests[0].value.clone())
+        );
+    }
+
     #[test]
     fn test_restart_lifecycle() {
         let path = sov_schema_db::temppath::TempPath::new();
@@ -1,13 +1,16 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use sov_rollup_interface::stf::Event;
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use crate::{
     internal_cache::{OrderedReadsAndWrites, StorageInternalCache},
     storage::{StorageKey, StorageValue},
     Prefix, Storage,
 };
-use sov_first_read_last_write_cache::{CacheKey, CacheValue};
+use sov_first_read_last_write_cache::{
+    cache::{CacheLog, ValueExists},
+    CacheKey, CacheValue,
+};
 
 /// A working set accumulates reads and writes on top of the underlying DB,
 /// automating witness creation.
@@ -15,6 +18,8 @@ pub struct Delta<S: Storage> {
     inner: S,
     witness: S::Witness,
     cache: StorageInternalCache,
+    /// The state of the working set a speculative delta was forked from, see [`StateSnapshot`].
+    base: Option<Arc<CacheLog>>,
 }
 
 /// A wrapper that adds additional writes on top of an underlying Delta.
@@ -49,13 +54,41 @@ enum ReadWriteSet<S: Storage> {
 /// This structure holds the read-write set and the events gathered during the execution of a transaction.
 pub struct WorkingSet<S: Storage> {
     read_write_set: ReadWriteSet<S>,
+    /// The writes of the nested calls in progress, the innermost last (see [`WorkingSet::execute_nested`]).
+    /// Reads missing from these layers go to the read-write set, so they are recorded even if a nested call fails.
+    nested_writes: Vec<HashMap<CacheKey, Option<CacheValue>>>,
     events: Vec<Event>,
 }
 
+/// A read-only copy of the state visible to a [`WorkingSet`], from which speculative working sets are forked.
+///
+/// A speculative working set reads the values missing from the snapshot from the storage, without recording them
+/// in any witness. Its reads and writes can then be replayed on top of the original working set with
+/// [`WorkingSet::replay`], which produces the same state and witness as executing the same operations on it directly.
+#[derive(Clone)]
+pub struct StateSnapshot<S: Storage> {
+    storage: S,
+    cache: Arc<CacheLog>,
+}
+
+impl<S: Storage> StateSnapshot<S> {
+    /// Forks a speculative working set from the snapshot.
+    pub fn fork(&self) -> WorkingSet<S> {
+        let mut delta = Delta::new(self.storage.clone());
+        delta.base = Some(self.cache.clone());
+        WorkingSet {
+            read_write_set: ReadWriteSet::Standard(delta),
+            nested_writes: Default::default(),
+            events: Default::default(),
+        }
+    }
+}
+
 impl<S: Storage> WorkingSet<S> {
     pub fn new(inner: S) -> Self {
         Self {
             read_write_set: ReadWriteSet::Standard(Delta::new(inner)),
+            nested_writes: Default::default(),
             events: Default::default(),
         }
     }
@@ -63,6 +96,7 @@ impl<S: Storage> WorkingSet<S> {
     pub fn with_witness(inner: S, witness: S::Witness) -> Self {
         Self {
             read_write_set: ReadWriteSet::Standard(Delta::with_witness(inner, witness)),
+            nested_writes: Default::default(),
             events: Default::default(),
         }
     }
@@ -77,6 +111,7 @@ impl<S: Storage> WorkingSet<S> {
 
         Self {
             read_write_set,
+            nested_writes: self.nested_writes,
             events: self.events,
         }
     }
@@ -89,6 +124,7 @@ impl<S: Storage> WorkingSet<S> {
 
         Self {
             read_write_set,
+            nested_writes: self.nested_writes,
             events: self.events,
         }
     }
@@ -100,12 +136,22 @@ impl<S: Storage> WorkingSet<S> {
         };
         Self {
             read_write_set,
+            nested_writes: self.nested_writes,
             // The `revert` removes all events associated with the transaction
             events: Vec::default(),
         }
     }
 
     pub(crate) fn get(&mut self, key: StorageKey) -> Option<StorageValue> {
+        if !self.nested_writes.is_empty() {
+            let cache_key = key.clone().as_cache_key();
+            for writes in self.nested_writes.iter().rev() {
+                if let Some(value) = writes.get(&cache_key) {
+                    return value.clone().map(StorageValue::new_from_cache_value);
+                }
+            }
+        }
+
         match &mut self.read_write_set {
             ReadWriteSet::Standard(s) => s.get(key),
             ReadWriteSet::Revertable(s) => s.get(key),
@@ -113,6 +159,11 @@ impl<S: Storage> WorkingSet<S> {
     }
 
     pub(crate) fn set(&mut self, key: StorageKey, value: StorageValue) {
+        if let Some(writes) = self.nested_writes.last_mut() {
+            writes.insert(key.as_cache_key(), Some(value.as_cache_value()));
+            return;
+        }
+
         match &mut self.read_write_set {
             ReadWriteSet::Standard(s) => s.set(key, value),
             ReadWriteSet::Revertable(s) => s.set(key, value),
@@ -120,6 +171,11 @@ impl<S: Storage> WorkingSet<S> {
     }
 
     pub(crate) fn delete(&mut self, key: StorageKey) {
+        if let Some(writes) = self.nested_writes.last_mut() {
+            writes.insert(key.as_cache_key(), None);
+            return;
+        }
+
         match &mut self.read_write_set {
             ReadWriteSet::Standard(s) => s.delete(key),
             ReadWriteSet::Revertable(s) => s.delete(key),
@@ -138,6 +194,42 @@ impl<S: Storage> WorkingSet<S> {
         &self.events
     }
 
+    /// Executes `f` as a nested call, whose events are attributed to `emitter` (see [`Event::with_emitter`]).
+    ///
+    /// The writes of `f` are kept in a separate layer on top of the working set: they are applied to the working set
+    /// if `f` succeeds and discarded with the events of `f` if it fails, while the writes and events made by the caller
+    /// before are kept. Like with [`WorkingSet::revert`], the reads of `f` are recorded in the witness in both cases.
+    pub fn execute_nested<T, E>(
+        &mut self,
+        emitter: &str,
+        f: impl FnOnce(&mut Self) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let caller_events = std::mem::take(&mut self.events);
+        self.nested_writes.push(HashMap::new());
+
+        let result = f(self);
+
+        let nested_writes = self
+            .nested_writes
+            .pop()
+            .expect("The layer of the nested call is pushed before the call");
+        let nested_events = std::mem::replace(&mut self.events, caller_events);
+        if result.is_ok() {
+            for (key, value) in nested_writes {
+                match value {
+                    Some(value) => self.set(key.into(), StorageValue::new_from_cache_value(value)),
+                    None => self.delete(key.into()),
+                }
+            }
+            self.events.extend(
+                nested_events
+                    .into_iter()
+                    .map(|event| event.with_emitter(emitter)),
+            );
+        }
+        result
+    }
+
     pub fn freeze(&mut self) -> (OrderedReadsAndWrites, S::Witness) {
         match &mut self.read_write_set {
             ReadWriteSet::Standard(delta) => delta.freeze(),
@@ -145,6 +237,57 @@ impl<S: Storage> WorkingSet<S> {
         }
     }
 
+    /// Takes a snapshot of the state visible to the working set, including the uncommitted writes.
+    pub fn snapshot(&self) -> StateSnapshot<S> {
+        let (delta, writes) = match &self.read_write_set {
+            ReadWriteSet::Standard(delta) => (delta, None),
+            ReadWriteSet::Revertable(revertable) => (&revertable.inner, Some(&revertable.writes)),
+        };
+
+        let mut cache = delta
+            .base
+            .as_ref()
+            .map(|base| base.as_ref().clone())
+            .unwrap_or_default();
+        cache
+            .merge_left(delta.cache.tx_cache.clone())
+            // It is ok to panic here, the cache of a delta is always consistent with its base.
+            .unwrap_or_else(|e| panic!("Inconsistent working set: {e:?}"));
+        let nested_writes = self.nested_writes.iter().flatten();
+        for (key, value) in writes.into_iter().flatten().chain(nested_writes) {
+            cache.add_write(key.clone(), value.clone());
+        }
+
+        StateSnapshot {
+            storage: self.backing().clone(),
+            cache: Arc::new(cache),
+        }
+    }
+
+    /// Replays the reads and writes of a speculative working set (see [`StateSnapshot`]) on top of this working set.
+    /// The reads are replayed in the order in which the speculative working set did them, so they are recorded in the
+    /// witness exactly as if they were done directly on this working set.
+    ///
+    /// Returns `false` without applying any write as soon as a value read differs from the one the speculative
+    /// working set read. The operations which produced the accesses must then be executed again on this working set,
+    /// which is consistent with the reads already replayed.
+    pub fn replay(&mut self, accesses: OrderedReadsAndWrites) -> bool {
+        for (key, value) in accesses.ordered_reads {
+            let current = self.get(key.into()).map(StorageValue::as_cache_value);
+            if current != value {
+                return false;
+            }
+        }
+
+        for (key, value) in accesses.ordered_writes {
+            match value {
+                Some(value) => self.set(key.into(), StorageValue::new_from_cache_value(value)),
+                None => self.delete(key.into()),
+            }
+        }
+        true
+    }
+
     pub fn backing(&self) -> &S {
         match &self.read_write_set {
             ReadWriteSet::Standard(delta) => &delta.inner,
@@ -194,11 +337,7 @@ impl<S: Storage> RevertableDelta<S> {
 
 impl<S: Storage> Delta<S> {
     fn new(inner: S) -> Self {
-        Self {
-            inner,
-            witness: Default::default(),
-            cache: Default::default(),
-        }
+        Self::with_witness(inner, Default::default())
     }
 
     fn with_witness(inner: S, witness: S::Witness) -> Self {
@@ -206,6 +345,7 @@ impl<S: Storage> Delta<S> {
             inner,
             witness,
             cache: Default::default(),
+            base: None,
         }
     }
 
@@ -225,7 +365,17 @@ impl<S: Storage> Debug for Delta<S> {
 
 impl<S: Storage> Delta<S> {
     fn get(&mut self, key: StorageKey) -> Option<StorageValue> {
-        self.cache.get_or_fetch(key, &self.inner, &self.witness)
+        let Some(base) = &self.base else {
+            return self.cache.get_or_fetch(key, &self.inner, &self.witness);
+        };
+
+        let (inner, witness) = (&self.inner, &self.witness);
+        self.cache.get_or_fetch_with(key, |key| {
+            match base.get_value(&key.clone().as_cache_key()) {
+                ValueExists::Yes(value) => value.map(StorageValue::new_from_cache_value),
+                ValueExists::No => inner.get(key, witness),
+            }
+        })
     }
 
     fn set(&mut self, key: StorageKey, value: StorageValue) {
@@ -2,6 +2,7 @@ use std::path::Path;
 
 use super::*;
 use crate::{ArrayWitness, DefaultStorageSpec, ProverStorage};
+use sov_rollup_interface::stf::Event;
 
 enum Operation {
     Merge,
@@ -199,3 +200,85 @@ fn test_witness_roundtrip() {
             .expect("ZK validation should succeed");
     };
 }
+
+#[test]
+fn test_speculative_replay() {
+    let storage = InMemoryStorage::<DefaultStorageSpec>::new();
+    let counter = StateValue::new(Prefix::new(vec![0]));
+    let other = StateValue::new(Prefix::new(vec![1]));
+
+    let mut working_set = WorkingSet::new(storage.clone());
+    counter.set(&1u32, &mut working_set);
+    let snapshot = working_set.snapshot();
+
+    // Both speculative working sets see the uncommitted write of the original working set.
+    let mut increment = snapshot.fork();
+    let value: u32 = counter.get(&mut increment).unwrap();
+    counter.set(&(value + 1), &mut increment);
+    let mut double = snapshot.fork();
+    let value: u32 = counter.get(&mut double).unwrap();
+    counter.set(&(value * 2), &mut double);
+    other.set(&7u32, &mut double);
+
+    assert!(working_set.replay(increment.freeze().0));
+    assert_eq!(counter.get(&mut working_set), Some(2));
+
+    // The second execution read a value overwritten by the first one, so none of its writes is applied.
+    assert!(!working_set.replay(double.freeze().0));
+    assert_eq!(counter.get(&mut working_set), Some(2));
+    assert_eq!(other.get(&mut working_set), None::<u32>);
+}
+
+#[test]
+fn test_nested_execution() {
+    let storage = InMemoryStorage::<DefaultStorageSpec>::new();
+    let state_value = StateValue::new(Prefix::new(vec![0]));
+
+    for revertable in [false, true] {
+        let mut working_set = WorkingSet::new(storage.clone());
+        if revertable {
+            working_set = working_set.to_revertable();
+        }
+        state_value.set(&1u32, &mut working_set);
+        working_set.add_event("caller", "before");
+
+        // A failed nested call leaves the writes and events of the caller untouched.
+        let result: Result<(), ()> = working_set.execute_nested("callee", |working_set| {
+            state_value.set(&2u32, working_set);
+            working_set.add_event("callee", "failed");
+            Err(())
+        });
+        assert!(result.is_err());
+        assert_eq!(state_value.get(&mut working_set), Some(1));
+        assert_eq!(working_set.events(), &[Event::new("caller", "before")]);
+
+        let result: Result<(), ()> = working_set.execute_nested("callee", |working_set| {
+            state_value.set(&3u32, working_set);
+            working_set.add_event("update", "succeeded");
+            Ok(())
+        });
+        assert!(result.is_ok());
+        assert_eq!(state_value.get(&mut working_set), Some(3));
+        assert_eq!(
+            working_set.events(),
+            &[
+                Event::new("caller", "before"),
+                Event::new("update", "succeeded").with_emitter("callee")
+            ]
+        );
+
+        // A failed call nested in a successful one only discards its own writes.
+        let result: Result<(), ()> = working_set.execute_nested("outer", |working_set| {
+            state_value.set(&4u32, working_set);
+            let inner: Result<(), ()> = working_set.execute_nested("inner", |working_set| {
+                state_value.set(&5u32, working_set);
+                Err(())
+            });
+            assert!(inner.is_err());
+            assert_eq!(state_value.get(working_set), Some(4));
+            Ok(())
+        });
+        assert!(result.is_ok());
+        assert_eq!(state_value.get(&mut working_set), Some(4));
+    }
+}
@@ -1,30 +1,26 @@
 use crate::witness::Witness;
 use borsh::BorshSerialize;
 use jmt::{storage::TreeReader, OwnedValue};
-use sov_db::state_db::StateDB;
 
-pub struct TreeReadLogger<'a, W> {
-    state_db: StateDB,
+pub struct TreeReadLogger<'a, R, W> {
+    reader: R,
     witness: &'a W,
 }
 
-impl<'a, W: Witness> TreeReadLogger<'a, W> {
-    /// Creates a tree read logger wrapping the provided StateDB.
+impl<'a, R: TreeReader, W: Witness> TreeReadLogger<'a, R, W> {
+    /// Creates a tree read logger wrapping the provided tree reader, such as a `StateDB`.
     /// The logger is recording by default
-    pub fn with_db_and_witness(db: StateDB, witness: &'a W) -> Self {
-        Self {
-            state_db: db,
-            witness,
-        }
+    pub fn with_db_and_witness(reader: R, witness: &'a W) -> Self {
+        Self { reader, witness }
     }
 }
 
-impl<'a, W: Witness> TreeReader for TreeReadLogger<'a, W> {
+impl<'a, R: TreeReader, W: Witness> TreeReader for TreeReadLogger<'a, R, W> {
     fn get_node_option(
         &self,
         node_key: &jmt::storage::NodeKey,
     ) -> anyhow::Result<Option<jmt::storage::Node>> {
-        let node_opt = self.state_db.get_node_option(node_key)?;
+        let node_opt = self.reader.get_node_option(node_key)?;
         self.witness
             .add_hint(node_opt.as_ref().map(|node| node.try_to_vec().unwrap()));
         Ok(node_opt)
@@ -35,7 +31,7 @@ impl<'a, W: Witness> TreeReader for TreeReadLogger<'a, W> {
         max_version: jmt::Version,
         key_hash: jmt::KeyHash,
     ) -> anyhow::Result<Option<OwnedValue>> {
-        let value_opt = self.state_db.get_value_option(max_version, key_hash)?;
+        let value_opt = self.reader.get_value_option(max_version, key_hash)?;
         self.witness.add_hint(value_opt.clone());
         Ok(value_opt)
     }
@@ -27,7 +27,7 @@ pub enum ValueExists {
 /// CacheLog keeps track of the original and current values of each key accessed.
 /// By tracking original values, we can detect and eliminate write patterns where a key is
 /// changed temporarily and then reset to its original value
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct CacheLog {
     log: HashMap<CacheKey, Access>,
 }
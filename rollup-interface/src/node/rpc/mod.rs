@@ -111,6 +111,10 @@ pub struct SlotResponse<B, Tx> {
     pub batch_range: std::ops::Range<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub batches: Option<Vec<ItemOrHash<BatchResponse<B, Tx>>>>,
+    /// The data stored with the slot by the DA layer adapter, usually the serialized block header.
+    /// See `SlotData::extra_data_for_storage`.
+    #[serde(default, with = "rpc_hex")]
+    pub extra_data: Vec<u8>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -133,6 +137,54 @@ pub struct TxResponse<Tx> {
     pub custom_receipt: Tx,
 }
 
+/// The state root after a slot was executed, see [`LedgerRpcProvider::get_state_root`].
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct StateRootResponse {
+    #[serde(with = "rpc_hex")]
+    pub state_root: Vec<u8>,
+    /// The hash of the witness produced while executing the slot.
+    #[serde(with = "rpc_hex")]
+    pub witness_hash: [u8; 32],
+}
+
+/// A query for the events emitted with a given key, see [`LedgerRpcProvider::get_events_by_key`].
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EventsByKeyQuery {
+    pub key: EventKey,
+    /// Only return the events emitted by the transactions with numbers in this range.
+    /// Defaults to all transactions.
+    #[serde(default)]
+    pub tx_range: Option<std::ops::Range<u64>>,
+    /// The cursor returned with the previous page of the query. Starts from the beginning of `tx_range` if omitted.
+    #[serde(default)]
+    pub cursor: Option<EventCursor>,
+    /// The maximum number of events to return. Defaults to, and can't exceed, the maximum page size of the provider.
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+/// The position of an event in the `(tx number, event number)` order of the events with the same key.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct EventCursor {
+    pub tx_number: u64,
+    pub event_number: u64,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EventResponse {
+    pub number: u64,
+    pub tx_number: u64,
+    pub event: Event,
+}
+
+/// A page of the events matching an [`EventsByKeyQuery`], ordered by tx number and event number.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EventsByKeyResponse {
+    pub events: Vec<EventResponse>,
+    /// The cursor to query the next page with, `None` if there are no more events.
+    pub next_cursor: Option<EventCursor>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ItemOrHash<T> {
@@ -164,6 +216,12 @@ pub trait LedgerRpcProvider {
         &self,
         event_ids: &[EventIdentifier],
     ) -> Result<Vec<Option<Event>>, anyhow::Error>;
+    fn get_events_by_key(
+        &self,
+        query: &EventsByKeyQuery,
+    ) -> Result<EventsByKeyResponse, anyhow::Error>;
+    /// Returns the state root after the slot with the given number was executed, if the node recorded it.
+    fn get_state_root(&self, slot_number: u64) -> Result<Option<StateRootResponse>, anyhow::Error>;
     fn get_slot_by_hash<B: DeserializeOwned, T: DeserializeOwned>(
         &self,
         hash: &[u8; 32],
@@ -69,4 +69,10 @@ pub trait SlotData: Serialize + DeserializeOwned + PartialEq + core::fmt::Debug
     type BlockHeader: BlockHeaderTrait;
     fn hash(&self) -> [u8; 32];
     fn header(&self) -> &Self::BlockHeader;
+    /// The data stored in the ledger alongside the slot, such as the serialized block header and any
+    /// metadata needed to verify it. It allows the prover and light clients to reconstruct the header
+    /// without querying the DA layer. Stores nothing by default.
+    fn extra_data_for_storage(&self) -> Vec<u8> {
+        Vec::new()
+    }
 }
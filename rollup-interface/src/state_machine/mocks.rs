@@ -6,7 +6,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     da::BlobTransactionTrait,
-    traits::AddressTrait,
+    services::da::SlotData,
+    traits::{AddressTrait, BlockHeaderTrait, CanonicalHash},
     zk::traits::{Matches, Zkvm},
 };
 
@@ -121,3 +122,58 @@ impl<Address: AddressTrait> TestBlob<Address> {
         Self { address, data }
     }
 }
+
+/// A mock header of a DA layer block, identified by its `hash`.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+pub struct TestBlockHeader {
+    pub prev_hash: [u8; 32],
+    pub hash: [u8; 32],
+}
+
+impl CanonicalHash for TestBlockHeader {
+    type Output = [u8; 32];
+
+    fn hash(&self) -> Self::Output {
+        self.hash
+    }
+}
+
+impl BlockHeaderTrait for TestBlockHeader {
+    type Hash = [u8; 32];
+
+    fn prev_hash(&self) -> Self::Hash {
+        self.prev_hash
+    }
+}
+
+/// A mock DA layer block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestBlock {
+    pub header: TestBlockHeader,
+}
+
+impl TestBlock {
+    pub fn new(prev_hash: [u8; 32], hash: [u8; 32]) -> Self {
+        Self {
+            header: TestBlockHeader { prev_hash, hash },
+        }
+    }
+}
+
+impl SlotData for TestBlock {
+    type BlockHeader = TestBlockHeader;
+
+    fn hash(&self) -> [u8; 32] {
+        self.header.hash
+    }
+
+    fn header(&self) -> &Self::BlockHeader {
+        &self.header
+    }
+
+    fn extra_data_for_storage(&self) -> Vec<u8> {
+        self.header
+            .try_to_vec()
+            .expect("serialization to vec is infallible")
+    }
+}
@@ -111,6 +111,9 @@ pub trait StateTransitionFunction<Vm: Zkvm> {
 pub struct Event {
     key: EventKey,
     value: EventValue,
+    /// The module which emitted the event in a call from another module, `None` for the events of the called module.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    emitter: Option<String>,
 }
 
 impl Event {
@@ -118,6 +121,7 @@ impl Event {
         Self {
             key: EventKey(key.as_bytes().to_vec()),
             value: EventValue(value.as_bytes().to_vec()),
+            emitter: None,
         }
     }
 
@@ -128,6 +132,20 @@ impl Event {
     pub fn value(&self) -> &EventValue {
         &self.value
     }
+
+    pub fn emitter(&self) -> Option<&str> {
+        self.emitter.as_deref()
+    }
+
+    /// Attributes the event to `emitter`, the module which emitted it in a call from another module.
+    /// The key of the event, which the ledger indexes the event by, is unchanged. An event emitted in
+    /// nested calls keeps the innermost emitter.
+    pub fn with_emitter(mut self, emitter: &str) -> Self {
+        if self.emitter.is_none() {
+            self.emitter = Some(emitter.to_owned());
+        }
+        self
+    }
 }
 
 #[derive(
@@ -7,7 +7,7 @@ use serde::Serialize;
 // *Trait IFF there's an associated type that would otherwise have the same name
 
 pub trait BlockHeaderTrait: PartialEq + Debug + CanonicalHash<Output = Self::Hash> + Clone {
-    type Hash: Clone;
+    type Hash: Clone + AsRef<[u8]>;
     fn prev_hash(&self) -> Self::Hash;
 }
 